@@ -0,0 +1,163 @@
+use crate::piece::Direction;
+
+// tracks the held horizontal keys and decides when the active piece auto-shifts
+#[derive(Default)]
+pub(crate) struct AutoShift {
+    left_held: bool,
+    right_held: bool,
+    // the most recently pressed direction that is still held
+    direction: Option<Direction>,
+    // ticks the direction has been held
+    charge: u32,
+    // ticks since the last auto-shift once charged
+    repeat_timer: u32,
+    // ticks left before auto-shifting may resume after a spawn
+    cut_timer: u32,
+}
+
+impl AutoShift {
+    pub(crate) fn press(&mut self, direction: Direction) {
+        match direction {
+            Direction::Left => self.left_held = true,
+            Direction::Right => self.right_held = true,
+        }
+        self.direction = Some(direction);
+        self.charge = 0;
+        self.repeat_timer = 0;
+    }
+
+    pub(crate) fn release(&mut self, direction: Direction) {
+        let other_held = match direction {
+            Direction::Left => {
+                self.left_held = false;
+                self.right_held
+            },
+            Direction::Right => {
+                self.right_held = false;
+                self.left_held
+            },
+        };
+
+        if self.direction == Some(direction) {
+            self.direction = match (direction, other_held) {
+                (Direction::Left, true) => Some(Direction::Right),
+                (Direction::Right, true) => Some(Direction::Left),
+                (_, false) => None,
+            };
+            self.charge = 0;
+            self.repeat_timer = 0;
+        }
+    }
+
+    pub(crate) fn on_spawn(&mut self, preserve_charge: bool, das_cut: u32) {
+        if !preserve_charge {
+            self.charge = 0;
+            self.repeat_timer = 0;
+        }
+        self.cut_timer = das_cut;
+    }
+
+    // returns the direction to shift this tick and how many cells, u32::MAX meaning as far as possible
+    pub(crate) fn tick(&mut self, das: u32, arr: u32) -> Option<(Direction, u32)> {
+        // the cut runs out the same number of ticks after a spawn whether or not a key is held
+        if self.cut_timer > 0 {
+            self.cut_timer -= 1;
+            return None
+        }
+        let direction = self.direction?;
+
+        let shifts = if arr == 0 { u32::MAX } else { 1 };
+
+        if self.charge < das {
+            self.charge += 1;
+            if self.charge < das {
+                return None
+            }
+            // the first auto-shift happens as soon as the charge is full
+            self.repeat_timer = 0;
+            return Some((direction, shifts))
+        }
+
+        if arr == 0 {
+            return Some((direction, u32::MAX))
+        }
+
+        self.repeat_timer += 1;
+        if self.repeat_timer < arr {
+            return None
+        }
+        self.repeat_timer = 0;
+        Some((direction, 1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAS: u32 = 10;
+
+    // the tick of each auto-shift over the given ticks, counting from 1
+    fn shift_ticks(auto_shift: &mut AutoShift, arr: u32, ticks: u32) -> Vec<(u32, u32)> {
+        (1..=ticks)
+            .filter_map(|tick| auto_shift.tick(DAS, arr).map(|(_, shifts)| (tick, shifts)))
+            .collect()
+    }
+
+    fn charged(arr: u32) -> AutoShift {
+        let mut auto_shift = AutoShift::default();
+        auto_shift.press(Direction::Right);
+        assert_eq!(shift_ticks(&mut auto_shift, arr, DAS), [(DAS, if arr == 0 { u32::MAX } else { 1 })]);
+        auto_shift
+    }
+
+    #[test]
+    fn das_charge() {
+        let mut auto_shift = AutoShift::default();
+        auto_shift.press(Direction::Left);
+        for _ in 1..DAS {
+            assert_eq!(auto_shift.tick(DAS, 2), None);
+        }
+        assert_eq!(auto_shift.tick(DAS, 2), Some((Direction::Left, 1)));
+
+        // pressing the other way starts charging again
+        auto_shift.press(Direction::Right);
+        assert_eq!(shift_ticks(&mut auto_shift, 2, DAS), [(DAS, 1)]);
+    }
+
+    #[test]
+    fn arr_zero_shifts_to_the_wall_every_tick() {
+        let mut auto_shift = charged(0);
+        assert_eq!(shift_ticks(&mut auto_shift, 0, 3), [(1, u32::MAX), (2, u32::MAX), (3, u32::MAX)]);
+    }
+
+    #[test]
+    fn arr_repeats_one_cell_at_a_time() {
+        let mut auto_shift = charged(3);
+        assert_eq!(shift_ticks(&mut auto_shift, 3, 9), [(3, 1), (6, 1), (9, 1)]);
+    }
+
+    #[test]
+    fn charge_across_spawns() {
+        let mut auto_shift = charged(0);
+        auto_shift.on_spawn(true, 0);
+        assert_eq!(auto_shift.tick(DAS, 0), Some((Direction::Right, u32::MAX)));
+
+        auto_shift.on_spawn(false, 0);
+        assert_eq!(shift_ticks(&mut auto_shift, 0, DAS), [(DAS, u32::MAX)]);
+    }
+
+    #[test]
+    fn das_cut() {
+        let mut auto_shift = charged(0);
+        auto_shift.on_spawn(true, 5);
+        assert_eq!(shift_ticks(&mut auto_shift, 0, 6), [(6, u32::MAX)]);
+
+        // the cut runs out while nothing is held, so a later press charges as usual
+        let mut auto_shift = AutoShift::default();
+        auto_shift.on_spawn(true, 5);
+        assert_eq!(shift_ticks(&mut auto_shift, 0, 30), []);
+        auto_shift.press(Direction::Right);
+        assert_eq!(shift_ticks(&mut auto_shift, 0, DAS), [(DAS, u32::MAX)]);
+    }
+}
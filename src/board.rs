@@ -0,0 +1,225 @@
+use crate::piece::{Piece, PieceKind};
+use crate::rotation::Rotation;
+
+#[derive(Clone, Copy, Debug)]
+pub struct BoardConfig {
+    // columns, at most 16 so that a row fits in a u16 mask
+    pub width: usize,
+    // visible rows
+    pub height: usize,
+    // rows above the visible field that pieces spawn into, never drawn
+    pub hidden_rows: usize,
+    // pixels per cell when drawn
+    pub cell_size: i32,
+}
+
+impl Default for BoardConfig {
+    fn default() -> Self {
+        Self {
+            width: 10,
+            height: 20,
+            hidden_rows: 20,
+            cell_size: 30,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct Block {
+    pub kind: PieceKind,
+    pub row: i32,
+    pub col: i32,
+}
+
+// one bitmask per row, bit n set when column n is filled, with the colors kept alongside for drawing.
+// rows are numbered from the top of the hidden buffer, so the visible field starts at row hidden_rows
+#[derive(Clone)]
+pub struct Board {
+    width: usize,
+    height: usize,
+    hidden_rows: usize,
+    full_mask: u16,
+    rows: Vec<u16>,
+    colors: Vec<Vec<Option<PieceKind>>>,
+}
+
+impl Board {
+    pub fn new(config: &BoardConfig) -> Self {
+        assert!(
+            (4..=16).contains(&config.width),
+            "board width must be between 4 and 16 columns, got {}", config.width
+        );
+        assert!(config.height >= 4, "board height must be at least 4 rows, got {}", config.height);
+        assert!(config.hidden_rows >= 2, "board needs at least 2 hidden rows to spawn into, got {}", config.hidden_rows);
+
+        let total_rows = config.hidden_rows + config.height;
+        Self {
+            width: config.width,
+            height: config.height,
+            hidden_rows: config.hidden_rows,
+            full_mask: ((1u32 << config.width) - 1) as u16,
+            rows: vec![0; total_rows],
+            colors: vec![vec![None; config.width]; total_rows],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn hidden_rows(&self) -> usize {
+        self.hidden_rows
+    }
+
+    // the row whose bottom edge is the top of the visible field
+    pub fn spawn_row(&self) -> i32 {
+        self.hidden_rows as i32 - 1
+    }
+
+    pub fn rows(&self) -> &[u16] {
+        &self.rows
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.iter().all(|&row| row == 0)
+    }
+
+    pub fn blocks(&self) -> impl Iterator<Item = Block> + '_ {
+        self.colors.iter().enumerate().flat_map(|(row, colors)| {
+            colors.iter().enumerate().filter_map(move |(col, kind)| {
+                kind.map(|kind| Block { kind, row: row as i32, col: col as i32 })
+            })
+        })
+    }
+
+    // cells outside the walls, floor and top of the hidden buffer count as occupied
+    pub fn is_occupied(&self, row: i32, col: i32) -> bool {
+        if col < 0 || col >= self.width as i32 || row < 0 || row >= self.rows.len() as i32 {
+            return true
+        }
+        self.rows[row as usize] & (1 << col) != 0
+    }
+
+    pub fn fits<P: Piece + ?Sized>(&self, piece: &P, x: i32, y: i32, rotation: Rotation) -> bool {
+        for (offset, &bits) in piece.shape(rotation).iter().enumerate() {
+            if bits == 0 {
+                continue
+            }
+
+            let row = y + offset as i32;
+            if row < 0 || row >= self.rows.len() as i32 {
+                return false
+            }
+
+            let mask = match shift_mask(bits, x, self.full_mask) {
+                Some(mask) => mask,
+                None => return false,
+            };
+
+            if self.rows[row as usize] & mask != 0 {
+                return false
+            }
+        }
+        true
+    }
+
+    pub fn lock(&mut self, piece: &dyn Piece) {
+        for block in piece.blocks() {
+            self.rows[block.row as usize] |= 1 << block.col;
+            self.colors[block.row as usize][block.col as usize] = Some(block.kind);
+        }
+    }
+
+    pub fn full_lines(&self) -> usize {
+        self.rows.iter().filter(|&&row| row == self.full_mask).count()
+    }
+
+    // nothing would be left once the full lines are cleared
+    pub fn is_empty_after_clearing(&self) -> bool {
+        self.rows.iter().all(|&row| row == 0 || row == self.full_mask)
+    }
+
+    // fills the given columns of a row, for setting up boards in tests
+    #[cfg(test)]
+    pub(crate) fn fill(&mut self, row: usize, bits: u16) {
+        self.rows[row] |= bits;
+        for col in 0..self.width {
+            if bits & (1 << col) != 0 {
+                self.colors[row][col] = Some(PieceKind::Square);
+            }
+        }
+    }
+
+    // returns the number of rows cleared
+    pub fn clear_full_lines(&mut self) -> usize {
+        let mut cleared = 0;
+        let mut write = self.rows.len();
+        for read in (0..self.rows.len()).rev() {
+            if self.rows[read] == self.full_mask {
+                cleared += 1;
+                continue
+            }
+            write -= 1;
+            self.rows[write] = self.rows[read];
+            self.colors.swap(write, read);
+        }
+        for row in 0..write {
+            self.rows[row] = 0;
+            self.colors[row].fill(None);
+        }
+        cleared
+    }
+}
+
+// moves a row of a piece's bounding box to column x, or returns None if part of it would leave the board
+fn shift_mask(bits: u16, x: i32, full_mask: u16) -> Option<u16> {
+    if x >= 0 {
+        let mask = (bits as u32) << x;
+        if mask > full_mask as u32 {
+            return None
+        }
+        Some(mask as u16)
+    } else {
+        if bits & ((1 << -x) - 1) != 0 {
+            return None
+        }
+        Some(bits >> -x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::piece::{new_piece, PieceState};
+
+    #[test]
+    fn clear_full_lines_moves_rows_and_colors_down() {
+        let mut board = Board::new(&BoardConfig { width: 4, ..BoardConfig::default() });
+        board.fill(39, 0b1111);
+        board.fill(38, 0b1000);
+        board.fill(37, 0b1111);
+        let mut piece = new_piece(PieceKind::T, &board);
+        *piece.state_mut() = PieceState { x: 0, y: 35, rotation: Rotation::Spawn };
+        board.lock(piece.as_ref());
+
+        assert_eq!(board.full_lines(), 2);
+        assert_eq!(board.clear_full_lines(), 2);
+        assert_eq!(board.rows()[35..], [0, 0, 0b0010, 0b0111, 0b1000]);
+
+        let blocks: Vec<(i32, i32, PieceKind)> = board.blocks().map(|block| (block.row, block.col, block.kind)).collect();
+        assert_eq!(
+            blocks,
+            [
+                (37, 1, PieceKind::T),
+                (38, 0, PieceKind::T),
+                (38, 1, PieceKind::T),
+                (38, 2, PieceKind::T),
+                (39, 3, PieceKind::Square),
+            ]
+        );
+    }
+}
@@ -0,0 +1,32 @@
+use crate::scoring::Clear;
+
+// streaks carried from one lock to the next, for scoring and the HUD
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Chains {
+    // clearing pieces in a row minus one, None once a piece locks without clearing
+    pub combo: Option<u32>,
+    // difficult clears in a row minus one, None until the first and after any other clear
+    pub back_to_back: Option<u32>,
+}
+
+impl Chains {
+    // locks clearing nothing break the combo but leave the back-to-back chain alone
+    pub(crate) fn update(&mut self, clear: Clear) {
+        if clear.lines == 0 {
+            self.combo = None;
+            return
+        }
+
+        self.combo = Some(self.combo.map_or(0, |combo| combo + 1));
+        self.back_to_back = if clear.is_difficult() {
+            Some(self.back_to_back.map_or(0, |chain| chain + 1))
+        } else {
+            None
+        };
+    }
+
+    // the latest difficult clear followed another
+    pub fn is_back_to_back(&self) -> bool {
+        matches!(self.back_to_back, Some(chain) if chain > 0)
+    }
+}
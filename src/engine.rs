@@ -0,0 +1,1029 @@
+use std::collections::VecDeque;
+use std::mem;
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::auto_shift::AutoShift;
+use crate::board::{Board, BoardConfig};
+use crate::chains::Chains;
+use crate::gravity::{GravityCurve, MAX_GRAVITY};
+use crate::piece::{new_piece, Direction, Piece, PieceKind};
+use crate::randomizer::{Randomizer, RandomizerKind};
+use crate::rotation::{Rotation, RotationDirection};
+use crate::scoring::{Clear, Scoring, ScoringTable};
+use crate::spin::{detect_spin, LastRotation};
+
+// the engine always advances in logical ticks of this rate, however often it is drawn
+pub const TICKS_PER_SECOND: u32 = 60;
+
+#[derive(Clone, Debug)]
+pub struct EngineConfig {
+    pub board: BoardConfig,
+    pub gravity: GravityCurve,
+    pub start_level: u32,
+    // lines to clear for each level up, at least 1
+    pub lines_per_level: u32,
+    pub scoring: ScoringTable,
+    // points for perfect clears of 1 to 4 lines, times the level; None uses the scoring table's own
+    pub perfect_clear_bonus: Option<[u32; 4]>,
+    // also count immobile spins of pieces other than T, as some rule sets do
+    pub all_spin: bool,
+    // also end the game when only part of a piece locks above the visible field
+    pub partial_lock_out: bool,
+    pub hold_enabled: bool,
+    // upcoming pieces shown ahead of time, from 0 to 6
+    pub preview_count: usize,
+    pub randomizer: RandomizerKind,
+    // the same seed and inputs always play out the same game; None picks a fresh seed
+    pub seed: Option<u64>,
+    pub soft_drop: SoftDrop,
+    // ticks a grounded piece waits before locking
+    pub lock_delay: u32,
+    pub lock_reset: LockReset,
+    // ticks a horizontal key is held before the piece starts auto-shifting
+    pub das: u32,
+    // ticks between auto-shifts once charged, 0 moving the piece straight to the wall
+    pub arr: u32,
+    // keep a held key's charge when the next piece spawns
+    pub preserve_das: bool,
+    // ticks auto-shifting is suspended after each spawn, 0 to disable
+    pub das_cut: u32,
+    // ticks between a piece locking and the next one spawning, known as ARE
+    pub are: u32,
+    // ticks cleared lines stay on the board before the rows above fall
+    pub line_clear_delay: u32,
+    // ARE after a piece clears lines, following the line clear delay; None uses are
+    pub line_are: Option<u32>,
+    // rotate keys pressed during a delay and still held turn the next piece as it enters, if it
+    // fits that way (IRS); otherwise they are ignored
+    pub initial_rotation: bool,
+    // a hold key pressed during a delay and still held swaps the next piece before it enters
+    // (IHS); otherwise it is ignored
+    pub initial_hold: bool,
+}
+
+impl Default for EngineConfig {
+    fn default() -> Self {
+        Self {
+            board: BoardConfig::default(),
+            gravity: GravityCurve::Guideline,
+            start_level: 1,
+            lines_per_level: 10,
+            scoring: ScoringTable::Guideline,
+            perfect_clear_bonus: None,
+            all_spin: false,
+            partial_lock_out: false,
+            hold_enabled: true,
+            preview_count: 5,
+            randomizer: RandomizerKind::SevenBag,
+            seed: None,
+            soft_drop: SoftDrop::Factor(20.0),
+            lock_delay: 30,
+            lock_reset: LockReset::Move { limit: 15 },
+            das: 10,
+            arr: 2,
+            preserve_das: true,
+            das_cut: 0,
+            are: 0,
+            line_clear_delay: 0,
+            line_are: None,
+            initial_rotation: true,
+            initial_hold: true,
+        }
+    }
+}
+
+// what restarts the lock delay of a grounded piece
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LockReset {
+    // any successful move or rotation, up to limit times before the piece reaches a new lowest row
+    Move { limit: u32 },
+    // only falling to a new lowest row
+    Step,
+    // nothing, the delay counts every grounded tick of the piece
+    None,
+}
+
+// how much faster the piece falls while soft drop is held
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SoftDrop {
+    // multiplies gravity
+    Factor(f32),
+    // moves the piece straight to the bottom without locking it
+    Infinite,
+}
+
+// the ways a game can end
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TopOut {
+    // the next piece overlaps the stack where it spawns
+    BlockOut,
+    // a piece locked entirely above the visible field
+    LockOut,
+    // a piece locked partly above the visible field, only with partial_lock_out enabled
+    PartialLockOut,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    MoveLeft,
+    MoveRight,
+    RotateClockwise,
+    RotateCounterClockwise,
+    Rotate180,
+    SoftDrop,
+    HardDrop,
+    Hold,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Input {
+    Press(Action),
+    Release(Action),
+}
+
+// what the engine is doing; inputs other than held keys are saved for the next piece outside Falling
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Phase {
+    // a piece is in play
+    Falling,
+    // the cleared lines are still on the board
+    LineClear { ticks_left: u32 },
+    // waiting for the next piece to spawn
+    Entry { ticks_left: u32 },
+}
+
+// things that happened during a tick or input, collected until the frontend drains them
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GameEvent {
+    // a piece locked clearing lines, spinning or both
+    Clear(Clear),
+    // a piece cleared the given lines and left the board empty, sent after its Clear
+    PerfectClear { lines: u32 },
+}
+
+pub struct Engine {
+    config: EngineConfig,
+    board: Board,
+    phase: Phase,
+    // outside Falling, the piece that locked last
+    active_piece: Box<dyn Piece>,
+    // presses made outside Falling, applied in order once the next piece spawns
+    buffered_actions: Vec<Action>,
+    // rotate and hold keys currently down, in the order pressed, for IRS and IHS
+    held_actions: Vec<Action>,
+    soft_dropping: bool,
+    auto_shift: AutoShift,
+    // ticks the active piece has spent grounded since the lock delay was last reset
+    lock_timer: u32,
+    lock_resets: u32,
+    lowest_row: i32,
+    // cleared by any other move, so a piece only spins if it locks where a rotation left it
+    last_rotation: Option<LastRotation>,
+    level: u32,
+    // in G, the cells the active piece falls per tick at the current level
+    gravity: f32,
+    // fraction of a cell the active piece has fallen since it last moved down
+    gravity_progress: f32,
+    seed: u64,
+    rng: ChaCha8Rng,
+    randomizer: Box<dyn Randomizer>,
+    // always holds exactly preview_count pieces
+    queue: VecDeque<PieceKind>,
+    held_piece: Option<PieceKind>,
+    // set once the active piece has been swapped with the hold slot, cleared when a piece locks
+    hold_used: bool,
+    game_over: Option<TopOut>,
+    scoring: Scoring,
+    chains: Chains,
+    events: Vec<GameEvent>,
+    ticks: u64,
+    pieces_spawned: u64,
+    lines_cleared: u32,
+    pieces_locked: u32,
+}
+
+impl Engine {
+    pub fn new(config: EngineConfig) -> Self {
+        assert!(config.preview_count <= 6, "at most 6 preview pieces are supported, got {}", config.preview_count);
+        assert!(config.lines_per_level > 0, "lines_per_level must be at least 1");
+
+        let board = Board::new(&config.board);
+        let seed = config.seed.unwrap_or_else(|| rand::thread_rng().gen());
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let mut randomizer = config.randomizer.build();
+        let mut queue: VecDeque<PieceKind> = (0..=config.preview_count)
+            .map(|_| randomizer.next(&mut rng))
+            .collect();
+        let first = queue.pop_front().unwrap();
+
+        let mut engine = Self {
+            phase: Phase::Falling,
+            buffered_actions: Vec::new(),
+            held_actions: Vec::new(),
+            soft_dropping: false,
+            auto_shift: AutoShift::default(),
+            lock_timer: 0,
+            lock_resets: 0,
+            lowest_row: 0,
+            last_rotation: None,
+            level: config.start_level,
+            gravity: config.gravity.gravity(config.start_level),
+            gravity_progress: 0.0,
+            seed,
+            rng,
+            randomizer,
+            queue,
+            held_piece: None,
+            hold_used: false,
+            game_over: None,
+            scoring: Scoring::new(config.scoring, config.perfect_clear_bonus),
+            chains: Chains::default(),
+            events: Vec::new(),
+            ticks: 0,
+            pieces_spawned: 0,
+            lines_cleared: 0,
+            pieces_locked: 0,
+            active_piece: new_piece(first, &board),
+            board,
+            config,
+        };
+        engine.spawn_piece(first, Rotation::Spawn);
+        engine
+    }
+
+    pub fn config(&self) -> &EngineConfig {
+        &self.config
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    pub fn phase(&self) -> Phase {
+        self.phase
+    }
+
+    // None between pieces
+    pub fn active_piece(&self) -> Option<&dyn Piece> {
+        match self.phase {
+            Phase::Falling => Some(self.active_piece.as_ref()),
+            Phase::LineClear { .. } | Phase::Entry { .. } => None,
+        }
+    }
+
+    // where the active piece would land if dropped straight down
+    pub fn ghost_piece(&self) -> Option<Box<dyn Piece>> {
+        let mut ghost = self.active_piece()?.clone_box();
+        while ghost.drop_row(&self.board) {}
+        Some(ghost)
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn next_pieces(&self) -> impl Iterator<Item = PieceKind> + '_ {
+        self.queue.iter().copied()
+    }
+
+    pub fn held_piece(&self) -> Option<PieceKind> {
+        self.held_piece
+    }
+
+    pub fn can_hold(&self) -> bool {
+        self.config.hold_enabled && !self.hold_used
+    }
+
+    pub fn game_over(&self) -> Option<TopOut> {
+        self.game_over
+    }
+
+    pub fn drain_events(&mut self) -> impl Iterator<Item = GameEvent> + '_ {
+        self.events.drain(..)
+    }
+
+    pub fn ticks(&self) -> u64 {
+        self.ticks
+    }
+
+    // changes whenever a new active piece appears, including from the hold slot
+    pub fn pieces_spawned(&self) -> u64 {
+        self.pieces_spawned
+    }
+
+    pub fn lines_cleared(&self) -> u32 {
+        self.lines_cleared
+    }
+
+    pub fn pieces_locked(&self) -> u32 {
+        self.pieces_locked
+    }
+
+    pub fn scoring(&self) -> &Scoring {
+        &self.scoring
+    }
+
+    pub fn score(&self) -> u64 {
+        self.scoring.score()
+    }
+
+    pub fn chains(&self) -> Chains {
+        self.chains
+    }
+
+    pub fn level(&self) -> u32 {
+        self.level
+    }
+
+    // in G, the cells the active piece falls per tick at the current level
+    pub fn gravity(&self) -> f32 {
+        self.gravity
+    }
+
+    pub fn apply(&mut self, input: Input) {
+        if self.game_over.is_some() {
+            return
+        }
+        self.track_held(input);
+        if self.phase != Phase::Falling {
+            self.buffer(input);
+            return
+        }
+
+        let moved = match input {
+            Input::Press(Action::MoveLeft) => {
+                self.auto_shift.press(Direction::Left);
+                self.shift_active_piece(Direction::Left)
+            },
+            Input::Press(Action::MoveRight) => {
+                self.auto_shift.press(Direction::Right);
+                self.shift_active_piece(Direction::Right)
+            },
+            Input::Release(Action::MoveLeft) => {
+                self.auto_shift.release(Direction::Left);
+                false
+            },
+            Input::Release(Action::MoveRight) => {
+                self.auto_shift.release(Direction::Right);
+                false
+            },
+            Input::Press(Action::RotateClockwise) => self.rotate_active_piece(RotationDirection::Clockwise),
+            Input::Press(Action::RotateCounterClockwise) => {
+                self.rotate_active_piece(RotationDirection::CounterClockwise)
+            },
+            Input::Press(Action::Rotate180) => self.rotate_active_piece(RotationDirection::Half),
+            Input::Press(Action::SoftDrop) => {
+                self.soft_dropping = true;
+                false
+            },
+            Input::Release(Action::SoftDrop) => {
+                self.soft_dropping = false;
+                false
+            },
+            Input::Press(Action::HardDrop) => {
+                self.hard_drop();
+                false
+            },
+            Input::Press(Action::Hold) => {
+                self.hold_piece();
+                false
+            },
+            Input::Release(_) => false,
+        };
+
+        if moved {
+            self.reset_lock_delay_on_move();
+        }
+    }
+
+    pub fn tick(&mut self) {
+        if self.game_over.is_some() {
+            return
+        }
+        self.ticks += 1;
+
+        match self.phase {
+            Phase::Falling => self.tick_falling(),
+            Phase::LineClear { ticks_left } => {
+                // DAS keeps charging between pieces
+                self.auto_shift.tick(self.config.das, self.config.arr);
+                if ticks_left > 1 {
+                    self.phase = Phase::LineClear { ticks_left: ticks_left - 1 };
+                } else {
+                    self.board.clear_full_lines();
+                    self.start_entry(self.config.line_are.unwrap_or(self.config.are));
+                }
+            },
+            Phase::Entry { ticks_left } => {
+                self.auto_shift.tick(self.config.das, self.config.arr);
+                if ticks_left > 1 {
+                    self.phase = Phase::Entry { ticks_left: ticks_left - 1 };
+                } else {
+                    self.spawn_next();
+                }
+            },
+        }
+    }
+
+    // keeps track of the rotate and hold keys in every phase, so a key pressed before a lock and
+    // still down at the next spawn counts for IRS and IHS
+    fn track_held(&mut self, input: Input) {
+        match input {
+            Input::Press(
+                action @ (Action::RotateClockwise
+                | Action::RotateCounterClockwise
+                | Action::Rotate180
+                | Action::Hold),
+            ) => {
+                if !self.held_actions.contains(&action) {
+                    self.held_actions.push(action);
+                }
+            },
+            Input::Release(action) => self.held_actions.retain(|&held| held != action),
+            Input::Press(_) => (),
+        }
+    }
+
+    // keeps track of held keys while there is no piece in play, saving every press
+    fn buffer(&mut self, input: Input) {
+        match input {
+            Input::Press(Action::MoveLeft) => self.auto_shift.press(Direction::Left),
+            Input::Press(Action::MoveRight) => self.auto_shift.press(Direction::Right),
+            Input::Release(Action::MoveLeft) => self.auto_shift.release(Direction::Left),
+            Input::Release(Action::MoveRight) => self.auto_shift.release(Direction::Right),
+            Input::Press(Action::SoftDrop) => self.soft_dropping = true,
+            Input::Release(Action::SoftDrop) => self.soft_dropping = false,
+            Input::Press(action) => self.buffered_actions.push(action),
+            Input::Release(_) => (),
+        }
+    }
+
+    fn tick_falling(&mut self) {
+        if let Some((direction, shifts)) = self.auto_shift.tick(self.config.das, self.config.arr) {
+            let mut moved = false;
+            for _ in 0..shifts {
+                if !self.shift_active_piece(direction) {
+                    break
+                }
+                moved = true;
+            }
+            if moved {
+                self.reset_lock_delay_on_move();
+            }
+        }
+
+        let gravity = match (self.soft_dropping, self.config.soft_drop) {
+            (true, SoftDrop::Factor(factor)) => self.gravity * factor,
+            (true, SoftDrop::Infinite) => MAX_GRAVITY,
+            (false, _) => self.gravity,
+        };
+
+        let mut rows_dropped = 0;
+        if gravity >= MAX_GRAVITY {
+            while self.drop_active_piece() {
+                rows_dropped += 1;
+            }
+        } else {
+            self.gravity_progress += gravity;
+
+            while self.gravity_progress >= 1.0 {
+                self.gravity_progress -= 1.0;
+
+                if !self.drop_active_piece() {
+                    self.gravity_progress = 0.0;
+                    break
+                }
+                rows_dropped += 1;
+            }
+        }
+        if self.soft_dropping {
+            self.scoring.award_soft_drop(rows_dropped);
+        }
+
+        if self.is_grounded() {
+            self.lock_timer += 1;
+
+            let resets_exhausted = match self.config.lock_reset {
+                LockReset::Move { limit } => self.lock_resets >= limit,
+                LockReset::Step | LockReset::None => false,
+            };
+            if self.lock_timer >= self.config.lock_delay || resets_exhausted {
+                self.next_piece();
+            }
+        }
+    }
+
+    fn is_grounded(&self) -> bool {
+        let state = *self.active_piece.state();
+        !self.board.fits(self.active_piece.as_ref(), state.x, state.y + 1, state.rotation)
+    }
+
+    fn shift_active_piece(&mut self, direction: Direction) -> bool {
+        if !self.active_piece.shift(&self.board, direction) {
+            return false
+        }
+        self.last_rotation = None;
+        true
+    }
+
+    fn rotate_active_piece(&mut self, direction: RotationDirection) -> bool {
+        match self.active_piece.rotate(&self.board, direction) {
+            Some(kick) => {
+                self.last_rotation = Some(LastRotation { direction, kick });
+                true
+            },
+            None => false,
+        }
+    }
+
+    // moves the active piece down a row, restarting the lock delay when it reaches a new lowest row
+    fn drop_active_piece(&mut self) -> bool {
+        if !self.active_piece.drop_row(&self.board) {
+            return false
+        }
+        self.last_rotation = None;
+
+        let row = self.active_piece.state().y;
+        if row > self.lowest_row {
+            self.lowest_row = row;
+            match self.config.lock_reset {
+                LockReset::Move { .. } => {
+                    self.lock_timer = 0;
+                    self.lock_resets = 0;
+                },
+                LockReset::Step => self.lock_timer = 0,
+                LockReset::None => (),
+            }
+        }
+        true
+    }
+
+    fn reset_lock_delay_on_move(&mut self) {
+        if let LockReset::Move { limit } = self.config.lock_reset {
+            if self.lock_timer > 0 && self.lock_resets < limit {
+                self.lock_timer = 0;
+                self.lock_resets += 1;
+            }
+        }
+    }
+
+    fn next_piece(&mut self) {
+        let skyline = self.board.hidden_rows() as i32;
+        let blocks = self.active_piece.blocks();
+        let blocks_above_skyline = blocks.iter().filter(|block| block.row < skyline).count();
+        let spin = detect_spin(
+            &self.board,
+            self.active_piece.as_ref(),
+            self.last_rotation,
+            self.config.all_spin,
+        );
+
+        self.board.lock(self.active_piece.as_ref());
+        self.pieces_locked += 1;
+        let lines = self.board.full_lines() as u32;
+        let clear = Clear { lines, spin, perfect: lines > 0 && self.board.is_empty_after_clearing() };
+        self.chains.update(clear);
+        self.scoring.award_lock(clear, self.chains, self.level);
+        if lines > 0 || spin.is_some() {
+            self.events.push(GameEvent::Clear(clear));
+        }
+        if clear.perfect {
+            self.events.push(GameEvent::PerfectClear { lines });
+        }
+        self.lines_cleared += lines;
+        self.update_level();
+
+        if blocks_above_skyline == blocks.len() {
+            self.game_over = Some(TopOut::LockOut);
+            return
+        }
+        if self.config.partial_lock_out && blocks_above_skyline > 0 {
+            self.game_over = Some(TopOut::PartialLockOut);
+            return
+        }
+
+        self.hold_used = false;
+        if lines > 0 && self.config.line_clear_delay > 0 {
+            self.phase = Phase::LineClear { ticks_left: self.config.line_clear_delay };
+        } else if lines > 0 {
+            self.board.clear_full_lines();
+            self.start_entry(self.config.line_are.unwrap_or(self.config.are));
+        } else {
+            self.start_entry(self.config.are);
+        }
+    }
+
+    fn start_entry(&mut self, ticks: u32) {
+        if ticks > 0 {
+            self.phase = Phase::Entry { ticks_left: ticks };
+        } else {
+            self.spawn_next();
+        }
+    }
+
+    // spawns the next piece from the queue, applying the rotate and hold keys still held as it
+    // enters, when enabled, and playing the presses saved while waiting for it that those did
+    // not use afterwards
+    fn spawn_next(&mut self) {
+        let mut buffered = mem::take(&mut self.buffered_actions);
+        let mut used = |action: Action| {
+            if let Some(index) = buffered.iter().position(|&buffered| buffered == action) {
+                buffered.remove(index);
+            }
+        };
+        let mut next = self.take_next();
+
+        if self.config.initial_hold && self.can_hold() && self.held_actions.contains(&Action::Hold) {
+            used(Action::Hold);
+            next = match self.held_piece.replace(next) {
+                Some(kind) => kind,
+                None => self.take_next(),
+            };
+            self.hold_used = true;
+        }
+
+        let mut rotation = Rotation::Spawn;
+        if self.config.initial_rotation {
+            for &action in &self.held_actions {
+                let direction = match action {
+                    Action::RotateClockwise => RotationDirection::Clockwise,
+                    Action::RotateCounterClockwise => RotationDirection::CounterClockwise,
+                    Action::Rotate180 => RotationDirection::Half,
+                    _ => continue,
+                };
+                used(action);
+                rotation = rotation.rotated(direction);
+            }
+        }
+        self.spawn_piece(next, rotation);
+
+        for action in buffered {
+            self.apply(Input::Press(action));
+        }
+    }
+
+    fn update_level(&mut self) {
+        let level = self.config.start_level + self.lines_cleared / self.config.lines_per_level;
+        if level != self.level {
+            self.level = level;
+            self.gravity = self.config.gravity.gravity(level);
+        }
+    }
+
+    // swaps the active piece with the held one, or with a fresh piece when the slot is empty
+    fn hold_piece(&mut self) {
+        if !self.can_hold() {
+            return
+        }
+
+        let next = match self.held_piece {
+            Some(kind) => kind,
+            None => self.take_next(),
+        };
+        self.held_piece = Some(self.active_piece.kind());
+        self.hold_used = true;
+        self.spawn_piece(next, Rotation::Spawn);
+    }
+
+    fn take_next(&mut self) -> PieceKind {
+        let kind = self.randomizer.next(&mut self.rng);
+        self.queue.push_back(kind);
+        self.queue.pop_front().unwrap()
+    }
+
+    // enters turned to the given rotation when it fits there without kicks, or unturned otherwise
+    fn spawn_piece(&mut self, kind: PieceKind, rotation: Rotation) {
+        self.active_piece = new_piece(kind, &self.board);
+        self.phase = Phase::Falling;
+        self.pieces_spawned += 1;
+
+        let mut state = *self.active_piece.state();
+        if self.board.fits(self.active_piece.as_ref(), state.x, state.y, rotation) {
+            state.rotation = rotation;
+            *self.active_piece.state_mut() = state;
+        } else if !self.board.fits(self.active_piece.as_ref(), state.x, state.y, state.rotation) {
+            self.game_over = Some(TopOut::BlockOut);
+        }
+        // as in the guideline, the piece enters the visible field at once when nothing is below it
+        if self.game_over.is_none() {
+            self.active_piece.drop_row(&self.board);
+        }
+
+        self.gravity_progress = 0.0;
+        self.lock_timer = 0;
+        self.lock_resets = 0;
+        self.lowest_row = self.active_piece.state().y;
+        self.last_rotation = None;
+        self.auto_shift.on_spawn(self.config.preserve_das, self.config.das_cut);
+    }
+
+    fn hard_drop(&mut self) {
+        let mut rows_dropped = 0;
+        while self.active_piece.drop_row(&self.board) {
+            rows_dropped += 1;
+            self.last_rotation = None;
+        }
+        self.scoring.award_hard_drop(rows_dropped);
+        self.next_piece();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Range;
+
+    use super::*;
+
+    fn config_with(change: impl FnOnce(&mut EngineConfig)) -> EngineConfig {
+        let mut config = EngineConfig { seed: Some(7), ..EngineConfig::default() };
+        change(&mut config);
+        config
+    }
+
+    fn tick_times(engine: &mut Engine, ticks: u32) {
+        for _ in 0..ticks {
+            engine.tick();
+        }
+    }
+
+    #[test]
+    fn engine_is_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<Engine>();
+    }
+
+    #[test]
+    fn new_pieces_enter_the_visible_field() {
+        let engine = Engine::new(config_with(|_| ()));
+        let skyline = engine.board().hidden_rows() as i32;
+        let lowest = engine.active_piece().unwrap().blocks().iter().map(|block| block.row).max();
+
+        assert_eq!(lowest, Some(skyline));
+    }
+
+    // fills all but the leftmost column of the given rows, so none of them clear
+    fn stack(engine: &mut Engine, rows: Range<usize>) {
+        for row in rows {
+            engine.board.fill(row, 0b11_1111_1110);
+        }
+    }
+
+    #[test]
+    fn block_out() {
+        let mut engine = Engine::new(config_with(|_| ()));
+        stack(&mut engine, 18..40);
+        engine.spawn_piece(PieceKind::T, Rotation::Spawn);
+
+        assert_eq!(engine.game_over(), Some(TopOut::BlockOut));
+    }
+
+    #[test]
+    fn lock_out() {
+        let mut engine = Engine::new(config_with(|_| ()));
+        stack(&mut engine, 20..40);
+        engine.spawn_piece(PieceKind::T, Rotation::Spawn);
+        assert_eq!(engine.game_over(), None);
+
+        // nothing below the spawn rows is free, so the T locks entirely in the hidden rows
+        engine.apply(Input::Press(Action::HardDrop));
+        assert_eq!(engine.game_over(), Some(TopOut::LockOut));
+    }
+
+    #[test]
+    fn partial_lock_out() {
+        // the T locks against the left wall across the skyline, which only ends the game when enabled
+        for (partial_lock_out, game_over) in [(false, None), (true, Some(TopOut::PartialLockOut))] {
+            let mut engine = Engine::new(config_with(|config| config.partial_lock_out = partial_lock_out));
+            stack(&mut engine, 21..40);
+            engine.spawn_piece(PieceKind::T, Rotation::Spawn);
+            for _ in 0..3 {
+                engine.apply(Input::Press(Action::MoveLeft));
+                engine.apply(Input::Release(Action::MoveLeft));
+            }
+            engine.apply(Input::Press(Action::HardDrop));
+
+            assert_eq!(engine.game_over(), game_over);
+        }
+    }
+
+    fn tap(engine: &mut Engine, action: Action) {
+        engine.apply(Input::Press(action));
+        engine.apply(Input::Release(action));
+    }
+
+    // starts a T at the spawn position and drops it as far as it goes without locking it
+    fn land_t(engine: &mut Engine) {
+        engine.spawn_piece(PieceKind::T, Rotation::Spawn);
+        while engine.drop_active_piece() {}
+    }
+
+    #[test]
+    fn locks_after_the_lock_delay() {
+        let mut engine = Engine::new(config_with(|_| ()));
+        land_t(&mut engine);
+
+        tick_times(&mut engine, 29);
+        assert_eq!(engine.pieces_locked(), 0);
+        engine.tick();
+        assert_eq!(engine.pieces_locked(), 1);
+    }
+
+    #[test]
+    fn move_reset_on_shift_and_rotation() {
+        let mut engine = Engine::new(config_with(|_| ()));
+        land_t(&mut engine);
+        tick_times(&mut engine, 20);
+        tap(&mut engine, Action::MoveLeft);
+        tick_times(&mut engine, 29);
+        assert_eq!(engine.pieces_locked(), 0);
+        engine.tick();
+        assert_eq!(engine.pieces_locked(), 1);
+
+        // a hole under the T's center lets it turn in place and stay grounded
+        let mut engine = Engine::new(config_with(|_| ()));
+        engine.board.fill(39, 0b11_1111_0001);
+        land_t(&mut engine);
+        tick_times(&mut engine, 20);
+        tap(&mut engine, Action::RotateClockwise);
+        assert_eq!(engine.active_piece().unwrap().state().rotation, Rotation::Right);
+        tick_times(&mut engine, 29);
+        assert_eq!(engine.pieces_locked(), 0);
+        engine.tick();
+        assert_eq!(engine.pieces_locked(), 1);
+    }
+
+    #[test]
+    fn move_reset_limit_locks_at_once() {
+        let mut engine = Engine::new(config_with(|config| config.lock_reset = LockReset::Move { limit: 2 }));
+        land_t(&mut engine);
+        for action in [Action::MoveLeft, Action::MoveRight] {
+            engine.tick();
+            tap(&mut engine, action);
+        }
+        assert_eq!(engine.pieces_locked(), 0);
+
+        // the next grounded tick locks with the delay barely started
+        engine.tick();
+        assert_eq!(engine.pieces_locked(), 1);
+    }
+
+    #[test]
+    fn step_reset_only_on_a_new_lowest_row() {
+        // a T grounded on a ledge for 20 ticks is moved off it and falls a row to the floor
+        let play = |lock_reset: LockReset| {
+            let mut engine = Engine::new(config_with(|config| {
+                config.lock_reset = lock_reset;
+                config.gravity = GravityCurve::Custom(vec![1.0]);
+            }));
+            engine.board.fill(39, 0b00_0001_1111);
+            land_t(&mut engine);
+            tick_times(&mut engine, 20);
+            tap(&mut engine, Action::MoveRight);
+            tick_times(&mut engine, 5);
+            assert_eq!(engine.pieces_locked(), 0);
+            assert_eq!(engine.active_piece().unwrap().state().y, 37);
+            tap(&mut engine, Action::MoveRight);
+            engine
+        };
+
+        // moving on the ledge did not reset the delay, but falling to row 39 did
+        let mut engine = play(LockReset::Step);
+        tick_times(&mut engine, 29);
+        assert_eq!(engine.pieces_locked(), 0);
+        assert_eq!(engine.active_piece().unwrap().state().y, 38);
+        engine.tick();
+        assert_eq!(engine.pieces_locked(), 1);
+
+        // nothing resets it, so the delay runs out 30 grounded ticks after first landing
+        let mut engine = play(LockReset::None);
+        tick_times(&mut engine, 4);
+        assert_eq!(engine.pieces_locked(), 0);
+        engine.tick();
+        assert_eq!(engine.pieces_locked(), 1);
+    }
+
+    #[test]
+    fn entry_delay() {
+        let mut engine = Engine::new(config_with(|config| config.are = 5));
+        engine.apply(Input::Press(Action::HardDrop));
+        assert_eq!(engine.phase(), Phase::Entry { ticks_left: 5 });
+        assert!(engine.active_piece().is_none());
+
+        tick_times(&mut engine, 4);
+        assert_eq!(engine.phase(), Phase::Entry { ticks_left: 1 });
+        engine.tick();
+        assert_eq!(engine.phase(), Phase::Falling);
+        assert_eq!(engine.pieces_spawned(), 2);
+    }
+
+    #[test]
+    fn line_clear_delay_then_line_are() {
+        let mut engine = Engine::new(config_with(|config| {
+            config.board.width = 4;
+            config.are = 9;
+            config.line_clear_delay = 3;
+            config.line_are = Some(2);
+        }));
+        engine.spawn_piece(PieceKind::Straight, Rotation::Spawn);
+        engine.apply(Input::Press(Action::HardDrop));
+
+        assert_eq!(engine.phase(), Phase::LineClear { ticks_left: 3 });
+        assert_eq!(engine.board().full_lines(), 1);
+        assert_eq!(engine.lines_cleared(), 1);
+        let events: Vec<GameEvent> = engine.drain_events().collect();
+        assert_eq!(events[1], GameEvent::PerfectClear { lines: 1 });
+
+        tick_times(&mut engine, 3);
+        assert_eq!(engine.phase(), Phase::Entry { ticks_left: 2 });
+        assert!(engine.board().is_empty());
+
+        tick_times(&mut engine, 2);
+        assert_eq!(engine.phase(), Phase::Falling);
+    }
+
+    #[test]
+    fn initial_rotation_and_hold_from_held_keys() {
+        // hard drops the first piece and presses the action during the entry delay, releasing it
+        // again unless held, then waits for the next piece
+        let play = |config: &EngineConfig, action: Action, held: bool| {
+            let mut engine = Engine::new(config.clone());
+            engine.apply(Input::Press(Action::HardDrop));
+            engine.apply(Input::Press(action));
+            if !held {
+                engine.apply(Input::Release(action));
+            }
+            tick_times(&mut engine, 5);
+            engine
+        };
+        let enabled = config_with(|config| config.are = 5);
+        let disabled = config_with(|config| {
+            config.are = 5;
+            config.initial_rotation = false;
+            config.initial_hold = false;
+        });
+
+        // held through the delay, a rotation turns the piece as it enters rather than once it is in
+        let engine = play(&enabled, Action::RotateClockwise, true);
+        assert_eq!(engine.active_piece().unwrap().state().rotation, Rotation::Right);
+        assert_eq!(engine.last_rotation, None);
+
+        // tapped, or with IRS off, it is still played, as an ordinary rotation after the spawn
+        for engine in [play(&enabled, Action::RotateClockwise, false), play(&disabled, Action::RotateClockwise, true)] {
+            assert_eq!(engine.active_piece().unwrap().state().rotation, Rotation::Right);
+            assert!(engine.last_rotation.is_some());
+        }
+
+        // a held hold swaps the next piece for the one after it before either spawns
+        let upcoming: Vec<PieceKind> = Engine::new(enabled.clone()).next_pieces().take(2).collect();
+        let engine = play(&enabled, Action::Hold, true);
+        assert_eq!(engine.active_piece().unwrap().kind(), upcoming[1]);
+        assert_eq!(engine.held_piece(), Some(upcoming[0]));
+        assert_eq!(engine.pieces_spawned(), 2);
+
+        // tapped, or with IHS off, the next piece spawns and is then held
+        for engine in [play(&enabled, Action::Hold, false), play(&disabled, Action::Hold, true)] {
+            assert_eq!(engine.active_piece().unwrap().kind(), upcoming[1]);
+            assert_eq!(engine.held_piece(), Some(upcoming[0]));
+            assert_eq!(engine.pieces_spawned(), 3);
+        }
+
+        // a key pressed before the lock counts as long as it is still down at the spawn
+        let mut engine = Engine::new(enabled);
+        engine.apply(Input::Press(Action::RotateCounterClockwise));
+        engine.apply(Input::Press(Action::HardDrop));
+        tick_times(&mut engine, 5);
+        assert_eq!(engine.active_piece().unwrap().state().rotation, Rotation::Left);
+        assert_eq!(engine.last_rotation, None);
+    }
+
+    #[test]
+    fn same_seed_same_game() {
+        let play = || {
+            let mut engine = Engine::new(config_with(|config| config.randomizer = RandomizerKind::History));
+            let script = [
+                Action::MoveLeft,
+                Action::RotateClockwise,
+                Action::MoveRight,
+                Action::Hold,
+                Action::Rotate180,
+                Action::HardDrop,
+            ];
+            for (i, &action) in script.iter().cycle().take(600).enumerate() {
+                engine.apply(Input::Press(action));
+                tick_times(&mut engine, i as u32 % 4);
+                engine.apply(Input::Release(action));
+            }
+            engine
+        };
+
+        let (first, second) = (play(), play());
+        assert!(first.pieces_locked() > 0);
+        assert_eq!(first.board().rows(), second.board().rows());
+        assert_eq!(first.score(), second.score());
+        assert_eq!(first.pieces_locked(), second.pieces_locked());
+        assert_eq!(first.game_over(), second.game_over());
+        assert!(first.next_pieces().eq(second.next_pieces()));
+    }
+}
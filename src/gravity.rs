@@ -0,0 +1,101 @@
+// in G, the gravity at and above which a piece falls straight to the bottom every tick
+pub const MAX_GRAVITY: f32 = 20.0;
+
+// NES frames per row for levels 0 to 28, one frame from level 29 on
+const NES_FRAMES_PER_ROW: [u32; 29] = [
+    48, 43, 38, 33, 28, 23, 18, 13, 8, 6,
+    5, 5, 5, 4, 4, 4, 3, 3, 3, 2,
+    2, 2, 2, 2, 2, 2, 2, 2, 2,
+];
+
+// TGM internal gravity in 1/256 G, from each level in the first column onwards
+const TGM_GRAVITY: [(u32, u32); 30] = [
+    (0, 4), (30, 6), (35, 8), (40, 10), (50, 12), (60, 16), (70, 32), (80, 48), (90, 64), (100, 80),
+    (120, 96), (140, 112), (160, 128), (170, 144), (200, 4), (220, 32), (230, 64), (233, 96), (236, 128), (239, 160),
+    (243, 192), (247, 224), (251, 256), (300, 512), (330, 768), (360, 1024), (400, 1280), (420, 1024), (450, 768), (500, 5120),
+];
+
+// how gravity grows with the level
+#[derive(Clone, Debug, PartialEq)]
+pub enum GravityCurve {
+    // (0.8 - (level - 1) * 0.007) ^ (level - 1) seconds per row, from level 1, reaching 20G at level 19
+    Guideline,
+    // the NES frame counts from level 0, reaching one row a frame at level 29
+    Nes,
+    // the TGM table, indexed by its 0 to 999 levels, so best paired with lines_per_level of 1
+    Tgm,
+    // G for each level from 0, the last entry covering every level after it
+    Custom(Vec<f32>),
+}
+
+impl GravityCurve {
+    // in G, the cells the active piece falls per tick at the given level
+    pub fn gravity(&self, level: u32) -> f32 {
+        let gravity = match self {
+            GravityCurve::Guideline => {
+                let level = level.clamp(1, 20) as f32;
+                let seconds_per_row = (0.8 - (level - 1.0) * 0.007).powf(level - 1.0);
+                1.0 / (seconds_per_row * 60.0)
+            },
+            GravityCurve::Nes => {
+                let frames = NES_FRAMES_PER_ROW.get(level as usize).copied().unwrap_or(1);
+                1.0 / frames as f32
+            },
+            GravityCurve::Tgm => {
+                let (_, gravity) = TGM_GRAVITY
+                    .iter()
+                    .rev()
+                    .find(|&&(from, _)| level >= from)
+                    .unwrap();
+                *gravity as f32 / 256.0
+            },
+            GravityCurve::Custom(table) => {
+                let last = table.len().saturating_sub(1);
+                table.get((level as usize).min(last)).copied().unwrap_or(0.0)
+            },
+        };
+        gravity.min(MAX_GRAVITY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn guideline() {
+        assert_eq!(GravityCurve::Guideline.gravity(1), 1.0 / 60.0);
+        // level 0 plays like level 1
+        assert_eq!(GravityCurve::Guideline.gravity(0), 1.0 / 60.0);
+        assert!(GravityCurve::Guideline.gravity(18) < MAX_GRAVITY);
+        assert_eq!(GravityCurve::Guideline.gravity(19), MAX_GRAVITY);
+        assert_eq!(GravityCurve::Guideline.gravity(100), MAX_GRAVITY);
+    }
+
+    #[test]
+    fn nes_table_ends_at_level_28() {
+        assert_eq!(GravityCurve::Nes.gravity(0), 1.0 / 48.0);
+        assert_eq!(GravityCurve::Nes.gravity(28), 1.0 / 2.0);
+        assert_eq!(GravityCurve::Nes.gravity(29), 1.0);
+        assert_eq!(GravityCurve::Nes.gravity(1000), 1.0);
+    }
+
+    #[test]
+    fn tgm_steps_at_each_table_entry() {
+        assert_eq!(GravityCurve::Tgm.gravity(0), 4.0 / 256.0);
+        assert_eq!(GravityCurve::Tgm.gravity(29), 4.0 / 256.0);
+        assert_eq!(GravityCurve::Tgm.gravity(30), 6.0 / 256.0);
+        assert_eq!(GravityCurve::Tgm.gravity(199), 144.0 / 256.0);
+        assert_eq!(GravityCurve::Tgm.gravity(200), 4.0 / 256.0);
+        assert_eq!(GravityCurve::Tgm.gravity(499), 3.0);
+        assert_eq!(GravityCurve::Tgm.gravity(500), MAX_GRAVITY);
+    }
+
+    #[test]
+    fn custom_keeps_its_last_entry() {
+        let curve = GravityCurve::Custom(vec![0.5, 1.0, 40.0]);
+        assert_eq!(curve.gravity(0), 0.5);
+        assert_eq!(curve.gravity(1), 1.0);
+        assert_eq!(curve.gravity(9), MAX_GRAVITY);
+    }
+}
@@ -0,0 +1,28 @@
+mod auto_shift;
+mod board;
+mod chains;
+mod engine;
+mod gravity;
+mod piece;
+mod randomizer;
+mod rotation;
+mod scoring;
+mod spin;
+
+pub use board::{Block, Board, BoardConfig};
+pub use chains::Chains;
+pub use engine::{
+    Action, Engine, EngineConfig, GameEvent, Input, LockReset, Phase, SoftDrop, TopOut,
+    TICKS_PER_SECOND,
+};
+pub use gravity::{GravityCurve, MAX_GRAVITY};
+pub use piece::{
+    new_piece, CloneBoxPiece, Direction, LeftL, LeftSkew, Piece, PieceKind, PieceState, RightL,
+    RightSkew, Square, Straight, T,
+};
+pub use randomizer::{
+    BagRandomizer, HistoryRandomizer, NesRandomizer, PureRandomizer, Randomizer, RandomizerKind,
+};
+pub use rotation::{Rotation, RotationDirection};
+pub use scoring::{Clear, Scoring, ScoringTable};
+pub use spin::SpinKind;
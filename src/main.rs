@@ -1,1043 +1,502 @@
-use  std::cmp;
-use std::ops::ControlFlow;
-use rand::Rng;
-// use rand::prelude::*;
+use tetra::graphics::mesh::{Mesh, ShapeStyle};
 use tetra::graphics::scaling::{ScalingMode, ScreenScaler};
-use tetra::graphics::{self, Color, DrawParams, Texture};
-use tetra::input::{self, Key};
+use tetra::graphics::text::{Text, VectorFontBuilder};
+use tetra::graphics::{self, Color, DrawParams, Rectangle, Texture};
+use tetra::input::Key;
 use tetra::math::Vec2;
 // use tetra::window;
+use tetra::time::{self, Timestep};
 use tetra::{Context, ContextBuilder, Event, State};
+use std::time::Duration;
 // use image::GenericImageView;
-use num::Integer;
 
-mod filter_none;
-use filter_none::{filter_none, filter_none_mut};
+use rust_tetris::{
+    Action, Block, Clear, Engine, EngineConfig, GameEvent, Input, PieceKind, PieceState, SpinKind, TopOut,
+    TICKS_PER_SECOND,
+};
 
-const WINDOW_WIDTH: i32 = 300;
-const WINDOW_HEIGHT: i32 = 450;
+// width of the panels beside the playfield, in cells
+const SIDE_PANEL_CELLS: i32 = 5;
+// the most time caught up in one frame after a hitch
+const MAX_FRAME_TIME: Duration = Duration::from_millis(250);
+// how long a callout for a spin or tetris stays on screen, in ticks
+const CALLOUT_TICKS: u32 = 120;
+const PERFECT_CLEAR_TICKS: u32 = 180;
 
 #[derive(PartialEq)]
 enum PlayMode {
     Running,
     Paused,
+    GameOver,
 }
 
 fn main() -> tetra::Result {
-    ContextBuilder::new("Tetris", WINDOW_WIDTH as i32, WINDOW_HEIGHT as i32)
+    let config = EngineConfig::default();
+    let layout = Layout::new(&config);
+
+    ContextBuilder::new("Tetris", layout.width, layout.height)
+        .timestep(Timestep::Variable)
         .quit_on_escape(true)
         .resizable(true)
         .build()?
-        .run(GameState::new)
+        .run(|ctx| GameState::new(ctx, config))
 }
 
-struct GameState {
-    block_texture: Texture,
-    scaler: ScreenScaler,
-    lines: [Line; 15],
-    active_piece: Box<dyn Piece>,
-    velocity: f32,
-    play_mode: PlayMode,
+struct KeyBindings {
+    move_left: Key,
+    move_right: Key,
+    rotate_clockwise: Key,
+    rotate_counter_clockwise: Key,
+    rotate_180: Key,
+    soft_drop: Key,
+    hard_drop: Key,
+    hold: Key,
 }
 
-impl State for GameState {
-    fn update(&mut self, ctx: &mut Context) -> tetra::Result {
-        if self.play_mode == PlayMode::Paused {
-            return Ok(());
-        }
-
-        self.active_piece.blocks_mut().iter_mut().for_each(|block| {
-            block.y_pos_top += self.velocity
-        });
-
-        let mut collision = false;
-        let mut reached_floor = false;
-
-        if self.detect_collisions(&self.active_piece) {
-            collision = true
-        }
-
-        if !collision {
-            self.active_piece.blocks().iter().try_for_each(|active_block| {
-                if active_block.y_pos_bottom() > 450 as f32 {
-                    reached_floor = true;
-                    return ControlFlow::Break(())
-                }
-                ControlFlow::Continue(())
-            });
-        }
-
-        if collision || reached_floor {
-            self.next_piece()
-        }
-
-        Ok(())
-    }
-
-    fn draw(&mut self, ctx: &mut Context) -> tetra::Result {
-        graphics::set_canvas(ctx, self.scaler.canvas());
-        graphics::clear(ctx, Color::rgba8(255, 255, 255, 225));
-
-        for block in self.active_piece.blocks() {
-            self.block_texture.draw(
-                ctx,
-                DrawParams::new()
-                    .position(Vec2::new((block.col * 30) as f32, block.y_pos_top))
-                    .color(block.color)
-                    .scale(Vec2::new(30 as f32 / 16 as f32, 30 as f32 / 16 as f32))
-            )
-        };
-
-        self.lines.iter().for_each(|line| {
-            filter_none(line.blocks.iter()).for_each(|block| {
-                self.block_texture.draw(
-                    ctx,
-                    DrawParams::new()
-                        .position(Vec2::new((block.col * 30) as f32, block.y_pos_top))
-                        .color(block.color)
-                        .scale(Vec2::new(30 as f32 / 16 as f32, 30 as f32 / 16 as f32))
-                )
-            })
-        });
-
-        graphics::reset_canvas(ctx);
-        graphics::clear(ctx, Color::BLACK);
-        self.scaler.draw(ctx);
-
-        Ok(())
-    }
-
-    fn event(&mut self, _: &mut Context, event: Event) -> tetra::Result {
-        match event {
-            Event::Resized{ width, height } => {
-                self.scaler.set_outer_size(width, height);
-            },
-            Event::KeyPressed { key: Key::RightShift | Key::LeftShift } => self.toggle_pause(),
-            _ => (),
-        }
-
-        if self.play_mode == PlayMode::Paused {
-            return Ok(())
-        }
-
-        match event {
-            Event::KeyPressed{ key: key @ (Key::Right | Key::Left) }  => {
-                self.active_piece.shift(&self.lines, key);
-            }
-            Event::KeyPressed{ key: Key::Space } => self.active_piece.rotate(&self.lines),
-            Event::KeyPressed { key: Key::Down } => self.drop_piece(),
-            _ => (),
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self {
+            move_left: Key::Left,
+            move_right: Key::Right,
+            rotate_clockwise: Key::Space,
+            rotate_counter_clockwise: Key::Z,
+            rotate_180: Key::A,
+            soft_drop: Key::Down,
+            hard_drop: Key::Up,
+            hold: Key::C,
         }
-
-        Ok(())
     }
 }
 
-impl GameState {
-    fn new(ctx: &mut Context) -> tetra::Result<GameState> {
-        Ok(GameState {
-            block_texture: Texture::new(ctx, "/Users/sanford/rust_tetris/resources/block.png")?,
-            scaler: ScreenScaler::with_window_size(
-                ctx,
-                WINDOW_WIDTH,
-                WINDOW_HEIGHT,
-                ScalingMode::ShowAllPixelPerfect,
-            )?,
-
-            active_piece: Box::new(Square::new()),
-            lines: generate_lines(),
-            velocity: 1 as f32,
-            play_mode: PlayMode::Running,
-        })
-    }
-
-    fn next_piece(&mut self) {
-        self.active_piece.blocks_mut().iter_mut().for_each(|block| {
-
-            let line_num = block.y_pos_top as i32 / 30;
-
-            block.y_pos_top = (self.lines[line_num as usize].row * 30) as f32;
-
-            self.lines[line_num as usize].blocks[block.col as usize] = Some(*block);
-        });
-
-        let mut deleted_rows = vec![];
-        for (row, line) in self.lines.iter().enumerate() {
-            let mut full = true;
-            for block in line.blocks {
-                if let None = block {
-                    full = false;
-                    break
-                }
-            }
-            if full {
-                deleted_rows.push(row);
-            }
+impl KeyBindings {
+    fn action_for(&self, key: Key) -> Option<Action> {
+        match key {
+            key if key == self.move_left => Some(Action::MoveLeft),
+            key if key == self.move_right => Some(Action::MoveRight),
+            key if key == self.rotate_clockwise => Some(Action::RotateClockwise),
+            key if key == self.rotate_counter_clockwise => Some(Action::RotateCounterClockwise),
+            key if key == self.rotate_180 => Some(Action::Rotate180),
+            key if key == self.soft_drop => Some(Action::SoftDrop),
+            key if key == self.hard_drop => Some(Action::HardDrop),
+            key if key == self.hold => Some(Action::Hold),
+            _ => None,
         }
-        for row in deleted_rows {
-            drop_line(&mut self.lines, row as usize)
-        }
-
-        let n = rand::thread_rng().gen_range(0..7);
-        self.active_piece = match n {
-            0 => Box::new(Square::new()),
-            1 => Box::new(Straight::new()),
-            2 => Box::new(T::new()),
-            3 => Box::new(RightL::new()),
-            4 => Box::new(LeftL::new()),
-            5 => Box::new(RightSkew::new()),
-            6 => Box::new(LeftSkew::new()),
-            _ => panic!("unexpected number encountered"),
-        };
-
-        self.velocity = 1 as f32;
-    }
-
-    fn toggle_pause(&mut self) {
-        match self.play_mode {
-            PlayMode::Paused => self.play_mode = PlayMode::Running,
-            PlayMode::Running => self.play_mode = PlayMode::Paused,
-        }
-    }
-
-    fn drop_piece(&mut self) {
-        self.velocity = f32::max(self.velocity * 3 as f32, 10 as f32)
-    }
-
-    fn detect_collisions(&self, shadow_piece: &Box<dyn Piece>) -> bool {
-        let mut collision = false;
-        self.lines.iter().for_each(|line| {
-            filter_none(line.blocks.iter()).try_for_each(|line_block| {
-                shadow_piece.blocks().iter().try_for_each(|shadow_block| {
-                    if shadow_block.y_pos_bottom() > (line.row * 30) as f32
-                        && shadow_block.y_pos_top < (line.row * 30) as f32
-                        && line_block.col == shadow_block.col {
-                        collision = true;
-                        return ControlFlow::Break(())
-                    }
-                    ControlFlow::Continue(())
-                })
-            });
-        });
-        collision
     }
 }
 
-fn generate_lines() -> [Line; 15] {
-    let mut lines = [ Line{ row: 0, blocks: [None; 10] }; 15];
-
-    for i in 0..lines.len() {
-        lines[i].row = i as u32;
-    }
-
-    lines
+struct Settings {
+    show_ghost: bool,
 }
 
-fn drop_line(lines: &mut [Line; 15], deleted_row: usize) {
-    let max_row = lines.len() - 1;
-    for row in (1..=max_row).rev()  {
-        if row <= deleted_row  {
-            lines[row] = lines[row - 1];
-            lines[row].row = row as u32;
-            for block in filter_none_mut(lines[row].blocks.iter_mut()) {
-                block.y_pos_top += 30 as f32;
-            }
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            show_ghost: true,
         }
     }
-    lines[0] = Line{ row: 0, blocks: [None; 10] };
 }
 
-
+// pixel positions of the playfield and the panels beside it; the left panel holds the hold slot
+// and the score, the right one the previews
 #[derive(Clone, Copy)]
-struct Block {
-    color: Color,
-    col: i32,
-    y_pos_top: f32,
-}
+struct Layout {
+    cell_size: i32,
+    playfield_x: i32,
+    playfield_width: i32,
+    next_panel_x: i32,
+    width: i32,
+    height: i32,
+}
+
+impl Layout {
+    fn new(config: &EngineConfig) -> Self {
+        let cell_size = config.board.cell_size;
+        let left_panel_width = SIDE_PANEL_CELLS * cell_size;
+        let next_panel_width = if config.preview_count > 0 { SIDE_PANEL_CELLS * cell_size } else { 0 };
+        let playfield_width = config.board.width as i32 * cell_size;
 
-impl Block {
-    fn y_pos_bottom(&self) -> f32 {
-        self.y_pos_top + 30 as f32
+        Self {
+            cell_size,
+            playfield_x: left_panel_width,
+            playfield_width,
+            next_panel_x: left_panel_width + playfield_width,
+            width: left_panel_width + playfield_width + next_panel_width,
+            height: config.board.height as i32 * cell_size,
+        }
     }
 }
 
-#[derive(Clone, Copy)]
-struct Line {
-    row: u32,
-    blocks: [Option<Block>; 10],
+struct GameState {
+    block_texture: Texture,
+    scaler: ScreenScaler,
+    layout: Layout,
+    playfield_border: Mesh,
+    overlay: Mesh,
+    hold_text: Text,
+    next_text: Text,
+    stats_text: Text,
+    callout_text: Text,
+    callout_ticks: u32,
+    perfect_clear_text: Text,
+    perfect_clear_ticks: u32,
+    results_text: Text,
+    key_bindings: KeyBindings,
+    settings: Settings,
+    engine: Engine,
+    // real time not yet simulated, always less than one tick after update
+    accumulator: Duration,
+    // the active piece before the latest tick, tagged with the engine's spawn count
+    previous_piece: Option<(u64, PieceState)>,
+    play_mode: PlayMode,
 }
 
-trait CloneBoxPiece {
-    fn clone_box(&self) -> Box<dyn Piece>;
-}
+impl State for GameState {
+    fn update(&mut self, ctx: &mut Context) -> tetra::Result {
+        if self.play_mode != PlayMode::Running {
+            return Ok(());
+        }
 
-impl<T> CloneBoxPiece for T
-    where
-        T: 'static + Piece + Clone,
-{
-    fn clone_box(&self) -> Box<dyn Piece> {
-        Box::new(self.clone())
-    }
-}
+        let tick = Duration::from_secs(1) / TICKS_PER_SECOND;
+        self.accumulator += time::get_delta_time(ctx).min(MAX_FRAME_TIME);
 
-trait Piece : CloneBoxPiece {
-    fn blocks(&self) -> &Vec<Block>;
-    fn blocks_mut(&mut self) -> &mut Vec<Block>;
-    fn do_rotate(&mut self);
+        while self.accumulator >= tick {
+            self.accumulator -= tick;
+            self.previous_piece = self
+                .engine
+                .active_piece()
+                .map(|piece| (self.engine.pieces_spawned(), *piece.state()));
+            self.engine.tick();
+            self.callout_ticks = self.callout_ticks.saturating_sub(1);
+            self.perfect_clear_ticks = self.perfect_clear_ticks.saturating_sub(1);
 
-    fn enforce_boundaries_after_rotation(&mut self) {
-        let mut piece_shift = 0;
-        for block in self.blocks() {
-            if block.col < 0 {
-                let block_shift = -1 * block.col;
-                piece_shift = cmp::max(piece_shift, block_shift)
-            }
-            if block.col > 9 {
-                let block_shift = -1 * (block.col - 9);
-                piece_shift = cmp::min(piece_shift, block_shift)
+            if let Some(top_out) = self.engine.game_over() {
+                self.show_results(top_out);
+                break
             }
         }
-        if piece_shift != 0 {
-            for block in self.blocks_mut() {
-                block.col += piece_shift
-            }
-        }
-    }
 
-    fn shift(&mut self, lines: &[Line; 15], key: Key) -> bool {
-        let mut at_boundary = false;
-        self.blocks().iter().try_for_each(|block| {
-            if block.col == 0 && key == Key::Left  || block.col == 9 && key == Key::Right {
-                at_boundary = true;
-                return ControlFlow::Break(())
+        let events: Vec<GameEvent> = self.engine.drain_events().collect();
+        for event in events {
+            match event {
+                GameEvent::Clear(clear) => self.show_callout(clear),
+                GameEvent::PerfectClear { .. } => self.perfect_clear_ticks = PERFECT_CLEAR_TICKS,
             }
-            ControlFlow::Continue(())
-        });
-        if at_boundary {
-            return false
         }
 
-        let mut blocked = false;
-        self.blocks().iter().try_for_each(|block| {
-            let cur_row = block.y_pos_top as usize / 30;
-            let next_row = cmp::min(cur_row + 1, lines.len() - 1);
-            if key == Key::Left {
-                if let Some(_) = lines[cur_row].blocks[block.col as usize - 1] {
-                    blocked = true;
-                }
-                if let Some(_) = lines[next_row].blocks[block.col as usize - 1] {
-                    blocked = true;
-                }
-                return ControlFlow::Break(())
-            }
-            if key == Key::Right {
-                if let Some(_) = lines[cur_row].blocks[block.col as usize + 1] {
-                    blocked = true;
-                }
-                if let Some(_) = lines[next_row].blocks[block.col as usize + 1] {
-                    blocked = true;
-                }
-                return ControlFlow::Break(())
-            }
-            ControlFlow::Continue(())
-        });
-        if blocked {
-            return false
-        }
+        Ok(())
+    }
 
-        self.blocks_mut().iter_mut().for_each(|block| {
-            match key {
-                Key::Left => { block.col -= 1 },
-                Key::Right => { block.col += 1 },
-                _ => panic!("unexpected key type encountered: {:?} ", key),
-            }
-        });
+    fn draw(&mut self, ctx: &mut Context) -> tetra::Result {
+        graphics::set_canvas(ctx, self.scaler.canvas());
+        graphics::clear(ctx, Color::rgba8(255, 255, 255, 225));
 
-        true
-    }
+        let cell_size = self.layout.cell_size as f32;
+        let playfield_x = self.layout.playfield_x as f32;
 
-    // returns (right_shift, left_shift)
-    fn calculate_boundary_shifts(&self) -> (i32, i32) {
-        let mut right_shift = 0;
-        let mut left_shift = 0;
-        for block in self.blocks() {
-            if block.col < 0 {
-                let block_shift = -1 * block.col;
-                right_shift = cmp::max(right_shift, block_shift)
-            }
-            if block.col > 9 {
-                let block_shift = block.col - 9;
-                left_shift = cmp::max(left_shift, block_shift)
+        if let (true, Some(ghost)) = (self.settings.show_ghost, self.engine.ghost_piece()) {
+            for block in ghost.blocks() {
+                self.draw_block(ctx, block, 0.0, 0.3)
             }
         }
-        (right_shift, left_shift)
-    }
 
-    // returns (rightmost_col, leftmost_col)
-    fn calculate_edge_cols(&self) -> (i32, i32) {
-        let mut rightmost_col = 0;
-        let mut leftmost_col = 9;
-        for block in self.blocks() {
-            rightmost_col = cmp::max(rightmost_col, block.col);
-            leftmost_col = cmp::min(leftmost_col, block.col)
+        if let Some(piece) = self.engine.active_piece() {
+            let fall_offset = self.fall_offset(piece.state());
+            for block in piece.blocks() {
+                self.draw_block(ctx, block, fall_offset, 1.0)
+            };
         }
-        (rightmost_col, leftmost_col)
-    }
 
-    // return (right_shift, left_shift)
-    fn calculate_collision_shifts(&self, lines: &[Line; 15]) -> (i32, i32) {
-        let (rightmost_col, leftmost_col) = self.calculate_edge_cols();
-        let width = rightmost_col - leftmost_col + 1;
-        let even = Integer::is_even(&width);
-        
-        let mut center = if !even {
-            leftmost_col + width / 2
-        } else {
-            leftmost_col + width / 2 - 1
-        };
-        
-        let mut right_shift = 0;
-        let mut left_shift = 0;
-        for line in lines {
-            let mut right_shift_row = 0;
-            let mut left_shift_row = 0;
-            
-            for line_block in filter_none(line.blocks.iter()) {
-                for block in self.blocks() {
-                    if block.y_pos_bottom() > (line.row * 30) as f32
-                        && block.y_pos_top < (line.row * 30) as f32
-                        && line_block.col == block.col {
-                        
-                        if even {
-                            if block.col <= center {
-                                right_shift_row += 1;
-                            } else {
-                                left_shift_row += 1;
-                            }
-                        } else {
-                            if block.col < center {
-                                right_shift_row = 1;
-                            } else if block.col > center {
-                                left_shift_row = 1;
-                            } else {
-                                panic!("collision at center of odd-width piece")
-                            }
-                        }
-                    }
-                }
-            }
-            
-            right_shift = cmp::max(right_shift, right_shift_row);
-            left_shift = cmp::max(left_shift, left_shift_row);
+        for block in self.engine.board().blocks() {
+            self.draw_block(ctx, block, 0.0, 1.0)
         }
 
-        (right_shift, left_shift)
-    }
-
-    fn rotate(&mut self, lines: &[Line; 15])
-    {
-        let mut shadow = self.clone_box();
-
-        shadow.do_rotate();
-
-        let (boundary_right_shift, boundary_left_shift) = shadow.calculate_boundary_shifts();
-        let (collision_right_shift, collision_left_shift) = shadow.calculate_collision_shifts(lines);
+        self.playfield_border.draw(
+            ctx,
+            DrawParams::new()
+                .position(Vec2::new(playfield_x, 0.0))
+                .color(Color::BLACK),
+        );
 
-        let shift_right = cmp::max(boundary_right_shift, collision_right_shift);
-        let shift_left = cmp::max(boundary_left_shift, collision_left_shift);
-
-        if shift_right != 0 && shift_left != 0 {
-            return
-        }
-
-        let mut shadow_shift_successful = true;
-        let mut shadow_shift_right = shift_right;
-        let mut shadow_shift_left = shift_left;
-        while shadow_shift_right != 0 || shadow_shift_left != 0 {
-            if shadow_shift_right != 0 {
-                if shadow.shift(lines, Key::Right) {
-                    shadow_shift_right -= 1;
-                } else {
-                    shadow_shift_successful = false;
-                    break
-                }
-            }
-            if shadow_shift_left != 0 {
-                if shadow.shift(lines, Key::Left) {
-                    shadow_shift_left -= 1;
-                } else {
-                    shadow_shift_successful = false;
-                    break
-                }
+        if self.engine.config().hold_enabled {
+            self.hold_text.draw(
+                ctx,
+                DrawParams::new()
+                    .position(Vec2::new(cell_size / 2.0, cell_size / 2.0))
+                    .color(Color::BLACK),
+            );
+            if let Some(kind) = self.engine.held_piece() {
+                let alpha = if self.engine.can_hold() { 1.0 } else { 0.3 };
+                self.draw_preview(ctx, kind, Vec2::new(cell_size / 2.0, cell_size * 1.5), alpha);
             }
         }
 
-        if !shadow_shift_successful {
-            return
+        let chains = self.engine.chains();
+        self.stats_text.set_content(format!(
+            "SCORE\n{}\n\nLEVEL  {}\nLINES  {}\n\nCOMBO  {}\nB2B    {}",
+            self.engine.score(),
+            self.engine.level(),
+            self.engine.lines_cleared(),
+            chains.combo.map_or("-".to_string(), |combo| combo.to_string()),
+            chains.back_to_back.map_or("-".to_string(), |chain| chain.to_string()),
+        ));
+        self.stats_text.draw(
+            ctx,
+            DrawParams::new()
+                .position(Vec2::new(cell_size / 2.0, cell_size * 6.0))
+                .color(Color::BLACK),
+        );
+
+        if self.callout_ticks > 0 {
+            self.callout_text.draw(
+                ctx,
+                DrawParams::new()
+                    .position(Vec2::new(cell_size / 2.0, cell_size * 14.0))
+                    .color(Color::BLACK),
+            );
+        }
+
+        if self.perfect_clear_ticks > 0 {
+            if let Some(bounds) = self.perfect_clear_text.get_bounds(ctx) {
+                let position = Vec2::new(
+                    playfield_x + (self.layout.playfield_width as f32 - bounds.width) / 2.0,
+                    (self.layout.height as f32 - bounds.height) / 3.0,
+                );
+                self.perfect_clear_text.draw(ctx, DrawParams::new().position(position).color(Color::rgb8(255, 140, 0)));
+            }
         }
 
-        self.do_rotate();
-
-        let mut real_shift_right = shift_right;
-        let mut real_shift_left = shift_left;
-        while real_shift_right != 0 || real_shift_left != 0 {
-            if real_shift_right != 0 {
-                self.shift(lines, Key::Right);
-                real_shift_right -= 1
-            }
-            if real_shift_left != 0 {
-                self.shift(lines, Key::Left);
-                real_shift_left -= 1
+        if self.engine.config().preview_count > 0 {
+            let next_panel_x = self.layout.next_panel_x as f32;
+            self.next_text.draw(
+                ctx,
+                DrawParams::new()
+                    .position(Vec2::new(next_panel_x + cell_size / 2.0, cell_size / 2.0))
+                    .color(Color::BLACK),
+            );
+            for (i, kind) in self.engine.next_pieces().enumerate() {
+                let origin = Vec2::new(next_panel_x + cell_size / 2.0, cell_size * (1.5 + 3.0 * i as f32));
+                self.draw_preview(ctx, kind, origin, 1.0);
             }
         }
-    }
-}
-
-#[derive(Clone)]
-struct Square {
-    blocks: Vec<Block>,
-}
 
-impl Piece for Square {
-    fn blocks(&self) -> &Vec<Block> {
-        &self.blocks
-    }
-    fn blocks_mut(&mut self) -> &mut Vec<Block> { &mut self.blocks }
-    fn do_rotate(&mut self) {}
-}
-
-impl Square {
-    fn new() -> Self {
-        Self {
-            blocks: vec![
-                Block {
-                    color: Color::rgba8(245, 40, 145, 204),
-                    col: 4,
-                    y_pos_top: -30 as f32,
-                },
-                Block {
-                    color: Color::rgba8(245, 40, 145, 204),
-                    col: 5,
-                    y_pos_top: -30 as f32,
-                },
-                Block {
-                    color: Color::rgba8(245, 40, 145, 204),
-                    col: 4,
-                    y_pos_top: -60 as f32,
-                },
-                Block {
-                    color: Color::rgba8(245, 40, 145, 204),
-                    col: 5,
-                    y_pos_top: -60 as f32,
-                },
-            ],
+        if self.play_mode == PlayMode::GameOver {
+            self.overlay.draw(ctx, DrawParams::new().color(Color::rgba(0.0, 0.0, 0.0, 0.7)));
+            self.results_text.draw(ctx, Vec2::new(playfield_x + cell_size / 2.0, cell_size * 2.0));
         }
-    }
-}
 
-#[derive(Clone)]
-struct Straight {
-    blocks: Vec<Block>,
-    rotation: u32,
-}
+        graphics::reset_canvas(ctx);
+        graphics::clear(ctx, Color::BLACK);
+        self.scaler.draw(ctx);
 
-impl Piece for Straight {
-    fn blocks(&self) -> &Vec<Block> {
-        &self.blocks
+        Ok(())
     }
-    fn blocks_mut(&mut self) -> &mut Vec<Block> { &mut self.blocks }
-
-    fn do_rotate(&mut self) {
-        match self.rotation {
-            0 => {
-                self.blocks_mut()[0].col += -1;
-                self.blocks_mut()[0].y_pos_top += -30 as f32;
-
-                self.blocks_mut()[2].col += 1;
-                self.blocks_mut()[2].y_pos_top += 30 as f32;
-
-                self.blocks_mut()[3].col += 2;
-                self.blocks_mut()[3].y_pos_top += 60 as f32;
-            },
-            90 => {
-                self.blocks_mut()[0].col += 1;
-                self.blocks_mut()[0].y_pos_top += -30 as f32;
-
-                self.blocks_mut()[2].col += -1;
-                self.blocks_mut()[2].y_pos_top += 30 as f32;
-
-                self.blocks_mut()[3].col += -2;
-                self.blocks_mut()[3].y_pos_top += 60 as f32;
-            },
-            180 => {
-                self.blocks_mut()[0].col += 1;
-                self.blocks_mut()[0].y_pos_top += 30 as f32;
 
-                self.blocks_mut()[2].col += -1;
-                self.blocks_mut()[2].y_pos_top += -30 as f32;
-
-                self.blocks_mut()[3].col += -2;
-                self.blocks_mut()[3].y_pos_top += -60 as f32;
-            },
-            270 => {
-                self.blocks_mut()[0].col += -1;
-                self.blocks_mut()[0].y_pos_top += 30 as f32;
-
-                self.blocks_mut()[2].col += 1;
-                self.blocks_mut()[2].y_pos_top += -30 as f32;
-
-                self.blocks_mut()[3].col += 2;
-                self.blocks_mut()[3].y_pos_top += -60 as f32;
+    fn event(&mut self, _: &mut Context, event: Event) -> tetra::Result {
+        match event {
+            Event::Resized{ width, height } => {
+                self.scaler.set_outer_size(width, height);
             },
-            _ => panic!("received unexpected rotation value: {}", self.rotation),
-        }
-
-        self.rotation = (self.rotation + 90).rem_euclid(360);
-    }
-}
-
-impl Straight {
-    fn new() -> Self {
-        Self {
-            blocks: vec![
-                Block {
-                    color: Color::rgba8(61, 139, 232, 117),
-                    col: 4,
-                    y_pos_top: -30 as f32,
-                },
-                Block {
-                    color: Color::rgba8(61, 139, 232, 117),
-                    col: 4,
-                    y_pos_top: -60 as f32,
-                },
-                Block {
-                    color: Color::rgba8(61, 139, 232, 117),
-                    col: 4,
-                    y_pos_top: -90 as f32,
-                },
-                Block {
-                    color: Color::rgba8(61, 139, 232, 117),
-                    col: 4,
-                    y_pos_top: -120 as f32,
-                },
-            ],
-            rotation: 0,
+            Event::KeyPressed { key: Key::RightShift | Key::LeftShift } => self.toggle_pause(),
+            Event::KeyPressed { key: Key::G } => self.settings.show_ghost = !self.settings.show_ghost,
+            Event::KeyPressed { key: Key::Enter } if self.play_mode == PlayMode::GameOver => self.restart(),
+            _ => (),
         }
-    }
-}
 
-#[derive(Clone)]
-struct T {
-    blocks: Vec<Block>,
-    rotation: u32,
-}
-
-impl Piece for T {
-    fn blocks(&self) -> &Vec<Block> {
-        &self.blocks
-    }
-    fn blocks_mut(&mut self) -> &mut Vec<Block> { &mut self.blocks }
-
-    fn do_rotate(&mut self) {
-        match self.rotation {
-            0 => {
-                self.blocks_mut()[0].col += 1;
-                self.blocks_mut()[0].y_pos_top += -30 as f32;
-
-                self.blocks_mut()[2].col += -1;
-                self.blocks_mut()[2].y_pos_top += 30 as f32;
-
-                self.blocks_mut()[3].col += 1;
-                self.blocks_mut()[3].y_pos_top += 30 as f32;
-            },
-            90 => {
-                self.blocks_mut()[0].col += 1;
-                self.blocks_mut()[0].y_pos_top += 30 as f32;
-
-                self.blocks_mut()[2].col += -1;
-                self.blocks_mut()[2].y_pos_top += -30 as f32;
-
-                self.blocks_mut()[3].col += -1;
-                self.blocks_mut()[3].y_pos_top += 30 as f32;
-            },
-            180 => {
-                self.blocks_mut()[0].col += -1;
-                self.blocks_mut()[0].y_pos_top += 30 as f32;
-
-                self.blocks_mut()[2].col += 1;
-                self.blocks_mut()[2].y_pos_top += -30 as f32;
-
-                self.blocks_mut()[3].col += -1;
-                self.blocks_mut()[3].y_pos_top += -30 as f32;
+        // releases still go through while paused so no key is left held down afterwards
+        match event {
+            Event::KeyPressed { key } if self.play_mode == PlayMode::Running => {
+                if let Some(action) = self.key_bindings.action_for(key) {
+                    self.engine.apply(Input::Press(action))
+                }
             },
-            270 => {
-                self.blocks_mut()[0].col += -1;
-                self.blocks_mut()[0].y_pos_top += -30 as f32;
-
-                self.blocks_mut()[2].col += 1;
-                self.blocks_mut()[2].y_pos_top += 30 as f32;
-
-                self.blocks_mut()[3].col += 1;
-                self.blocks_mut()[3].y_pos_top += -30 as f32;
+            Event::KeyReleased { key } => {
+                if let Some(action) = self.key_bindings.action_for(key) {
+                    self.engine.apply(Input::Release(action))
+                }
             },
-            _ => panic!("received unexpected rotation value: {}", self.rotation),
+            _ => (),
         }
-        self.enforce_boundaries_after_rotation();
-        self.rotation = (self.rotation + 90).rem_euclid(360);
-    }
-}
 
-impl T {
-    fn new() -> Self {
-        Self {
-            blocks: vec![
-                Block {
-                    color: Color::rgba8(47, 94, 68, 196),
-                    col: 3,
-                    y_pos_top: -30 as f32,
-                },
-                Block {
-                    color: Color::rgba8(47, 94, 68, 196),
-                    col: 4,
-                    y_pos_top: -30 as f32,
-                },
-                Block {
-                    color: Color::rgba8(47, 94, 68, 196),
-                    col: 5,
-                    y_pos_top: -30 as f32,
-                },
-                Block {
-                    color: Color::rgba8(47, 94, 68, 196),
-                    col: 4,
-                    y_pos_top: -60 as f32,
-                },
-            ],
-            rotation: 0,
-        }
+        Ok(())
     }
 }
 
-#[derive(Clone)]
-struct RightL {
-    blocks: Vec<Block>,
-    rotation: u32,
-}
-
-impl Piece for RightL {
-    fn blocks(&self) -> &Vec<Block> {
-        &self.blocks
-    }
-    fn blocks_mut(&mut self) -> &mut Vec<Block> { &mut self.blocks }
-
-    fn do_rotate(&mut self) {
-        match self.rotation {
-            0 => {
-                self.blocks_mut()[1].col += -1;
-                self.blocks_mut()[1].y_pos_top += 30 as f32;
-
-                self.blocks_mut()[2].col += 1;
-                self.blocks_mut()[2].y_pos_top += 30 as f32;
-
-                self.blocks_mut()[3].col += 2;
-                self.blocks_mut()[3].y_pos_top += 60 as f32;
-            },
-            90 => {
-                self.blocks_mut()[1].col += -1;
-                self.blocks_mut()[1].y_pos_top += -30 as f32;
-
-                self.blocks_mut()[2].col += -1;
-                self.blocks_mut()[2].y_pos_top += 30 as f32;
-
-                self.blocks_mut()[3].col += -2;
-                self.blocks_mut()[3].y_pos_top += 60 as f32;
-            },
-            180 => {
-                self.blocks_mut()[1].col += 1;
-                self.blocks_mut()[1].y_pos_top += -30 as f32;
-
-                self.blocks_mut()[2].col += -1;
-                self.blocks_mut()[2].y_pos_top += -30 as f32;
-
-                self.blocks_mut()[3].col += -2;
-                self.blocks_mut()[3].y_pos_top += -60 as f32;
-            },
-            270 => {
-                self.blocks_mut()[1].col += 1;
-                self.blocks_mut()[1].y_pos_top += 30 as f32;
+impl GameState {
+    fn new(ctx: &mut Context, config: EngineConfig) -> tetra::Result<GameState> {
+        let layout = Layout::new(&config);
+        // compiled into the binary and parsed once for both sizes
+        let font_builder = VectorFontBuilder::from_file_data(include_bytes!("../resources/DejaVuSansMono.ttf"))?;
+        let font = font_builder.with_size(ctx, layout.cell_size as f32 * 0.6)?;
+        let large_font = font_builder.with_size(ctx, layout.cell_size as f32 * 1.2)?;
 
-                self.blocks_mut()[2].col += 1;
-                self.blocks_mut()[2].y_pos_top += -30 as f32;
+        Ok(GameState {
+            block_texture: Texture::new(ctx, "/Users/sanford/rust_tetris/resources/block.png")?,
+            playfield_border: Mesh::rectangle(
+                ctx,
+                ShapeStyle::Stroke(1.0),
+                Rectangle::new(0.0, 0.0, layout.playfield_width as f32, layout.height as f32),
+            )?,
+            overlay: Mesh::rectangle(
+                ctx,
+                ShapeStyle::Fill,
+                Rectangle::new(0.0, 0.0, layout.width as f32, layout.height as f32),
+            )?,
+            hold_text: Text::new("HOLD", font.clone()),
+            next_text: Text::new("NEXT", font.clone()),
+            stats_text: Text::new("", font.clone()),
+            callout_text: Text::new("", font.clone()),
+            callout_ticks: 0,
+            perfect_clear_text: Text::new("PERFECT\n CLEAR", large_font),
+            perfect_clear_ticks: 0,
+            results_text: Text::new("", font),
+            scaler: ScreenScaler::with_window_size(
+                ctx,
+                layout.width,
+                layout.height,
+                ScalingMode::ShowAllPixelPerfect,
+            )?,
+            layout,
 
-                self.blocks_mut()[3].col += 2;
-                self.blocks_mut()[3].y_pos_top += -60 as f32;
-            },
-            _ => panic!("received unexpected rotation value: {}", self.rotation),
-        }
-        self.enforce_boundaries_after_rotation();
-        self.rotation = (self.rotation + 90).rem_euclid(360);
+            key_bindings: KeyBindings::default(),
+            settings: Settings::default(),
+            engine: Engine::new(config),
+            accumulator: Duration::ZERO,
+            previous_piece: None,
+            play_mode: PlayMode::Running,
+        })
     }
-}
 
-impl RightL {
-    fn new() -> Self {
-        Self {
-            blocks: vec![
-                Block {
-                    color: Color::rgba8(249, 134, 36, 224),
-                    col: 4,
-                    y_pos_top: -30 as f32,
-                },
-                Block {
-                    color: Color::rgba8(249, 134, 36, 224),
-                    col: 5,
-                    y_pos_top: -30 as f32,
-                },
-                Block {
-                    color: Color::rgba8(249, 134, 36, 224),
-                    col: 4,
-                    y_pos_top: -60 as f32,
-                },
-                Block {
-                    color: Color::rgba8(249, 134, 36, 224),
-                    col: 4,
-                    y_pos_top: -90 as f32,
-                },
-            ],
-            rotation: 0,
+    fn toggle_pause(&mut self) {
+        match self.play_mode {
+            PlayMode::Paused => self.play_mode = PlayMode::Running,
+            PlayMode::Running => self.play_mode = PlayMode::Paused,
+            PlayMode::GameOver => (),
         }
     }
-}
 
-#[derive(Clone)]
-struct LeftL {
-    blocks: Vec<Block>,
-    rotation: u32,
-}
+    // names spins and tetrises, leaving plain clears of fewer lines unannounced
+    fn show_callout(&mut self, clear: Clear) {
+        let lines = match clear.lines {
+            0 => "",
+            1 => "SINGLE",
+            2 => "DOUBLE",
+            3 => "TRIPLE",
+            _ => "TETRIS",
+        };
+        let callout = match clear.spin {
+            Some(SpinKind::TSpin) => format!("T-SPIN\n{}", lines),
+            Some(SpinKind::MiniTSpin) => format!("MINI T-SPIN\n{}", lines),
+            Some(SpinKind::AllSpin(kind)) => format!("{}-SPIN\n{}", piece_letter(kind), lines),
+            None if clear.lines >= 4 => lines.to_string(),
+            None => return,
+        };
 
-impl Piece for LeftL {
-    fn blocks(&self) -> &Vec<Block> {
-        &self.blocks
+        self.callout_text.set_content(callout);
+        self.callout_ticks = CALLOUT_TICKS;
     }
-    fn blocks_mut(&mut self) -> &mut Vec<Block> { &mut self.blocks }
-
-    fn do_rotate(&mut self) {
-        match self.rotation {
-            0 => {
-                self.blocks_mut()[0].col += 1;
-                self.blocks_mut()[0].y_pos_top += -30 as f32;
-
-                self.blocks_mut()[2].col += 1;
-                self.blocks_mut()[2].y_pos_top += 30 as f32;
-
-                self.blocks_mut()[3].col += 2;
-                self.blocks_mut()[3].y_pos_top += 60 as f32;
-            },
-            90 => {
-                self.blocks_mut()[0].col += 1;
-                self.blocks_mut()[0].y_pos_top += 30 as f32;
 
-                self.blocks_mut()[2].col += -1;
-                self.blocks_mut()[2].y_pos_top += 30 as f32;
-
-                self.blocks_mut()[3].col += -2;
-                self.blocks_mut()[3].y_pos_top += 60 as f32;
-            },
-            180 => {
-                self.blocks_mut()[0].col += -1;
-                self.blocks_mut()[0].y_pos_top += 30 as f32;
-
-                self.blocks_mut()[2].col += -1;
-                self.blocks_mut()[2].y_pos_top += -30 as f32;
-
-                self.blocks_mut()[3].col += -2;
-                self.blocks_mut()[3].y_pos_top += -60 as f32;
-            },
-            270 => {
-                self.blocks_mut()[0].col += -1;
-                self.blocks_mut()[0].y_pos_top += -30 as f32;
-
-                self.blocks_mut()[2].col += 1;
-                self.blocks_mut()[2].y_pos_top += -30 as f32;
+    fn show_results(&mut self, top_out: TopOut) {
+        let reason = match top_out {
+            TopOut::BlockOut => "Block out",
+            TopOut::LockOut => "Lock out",
+            TopOut::PartialLockOut => "Partial lock out",
+        };
 
-                self.blocks_mut()[3].col += 2;
-                self.blocks_mut()[3].y_pos_top += -60 as f32;
+        self.results_text.set_content(format!(
+            "GAME OVER\n{}\n\nScore:  {}\nLevel:  {}\nLines:  {}\nPieces: {}\nSeed:   {}\n\nEnter to restart",
+            reason,
+            self.engine.score(),
+            self.engine.level(),
+            self.engine.lines_cleared(),
+            self.engine.pieces_locked(),
+            self.engine.seed(),
+        ));
+        self.play_mode = PlayMode::GameOver;
+    }
+
+    fn restart(&mut self) {
+        self.engine = Engine::new(self.engine.config().clone());
+        self.accumulator = Duration::ZERO;
+        self.previous_piece = None;
+        self.callout_ticks = 0;
+        self.perfect_clear_ticks = 0;
+        self.play_mode = PlayMode::Running;
+    }
+
+    // rows the active piece is drawn above its cell while it falls between ticks; only the fall
+    // is interpolated, so moves and rotations still show up the moment they happen
+    fn fall_offset(&self, current: &PieceState) -> f32 {
+        match self.previous_piece {
+            Some((spawned, previous))
+                if spawned == self.engine.pieces_spawned()
+                    && previous.rotation == current.rotation
+                    && previous.y < current.y =>
+            {
+                let blend = self.accumulator.as_secs_f32() * TICKS_PER_SECOND as f32;
+                (previous.y - current.y) as f32 * (1.0 - blend)
             },
-            _ => panic!("received unexpected rotation value: {}", self.rotation),
-        }
-        self.enforce_boundaries_after_rotation();
-        self.rotation = (self.rotation + 90).rem_euclid(360);
-    }
-}
-
-impl LeftL {
-    fn new() -> Self {
-        Self {
-            blocks: vec![
-                Block {
-                    color: Color::rgba8(176, 99, 246, 199),
-                    col: 4,
-                    y_pos_top: -30 as f32,
-                },
-                Block {
-                    color: Color::rgba8(176, 99, 246, 199),
-                    col: 5,
-                    y_pos_top: -30 as f32,
-                },
-                Block {
-                    color: Color::rgba8(176, 99, 246, 199),
-                    col: 5,
-                    y_pos_top: -60 as f32,
-                },
-                Block {
-                    color: Color::rgba8(176, 99, 246, 199),
-                    col: 5,
-                    y_pos_top: -90 as f32,
-                },
-            ],
-            rotation: 0,
+            _ => 0.0,
         }
     }
-}
 
-#[derive(Clone)]
-struct RightSkew {
-    blocks: Vec<Block>,
-    rotation: u32,
-}
-
-impl Piece for RightSkew {
-    fn blocks(&self) -> &Vec<Block> {
-        &self.blocks
+    fn draw_block(&self, ctx: &mut Context, block: Block, row_offset: f32, alpha: f32) {
+        let cell_size = self.layout.cell_size as f32;
+        let row = (block.row - self.engine.board().hidden_rows() as i32) as f32 + row_offset;
+        let position = Vec2::new(
+            self.layout.playfield_x as f32 + block.col as f32 * cell_size,
+            row * cell_size,
+        );
+        let mut color = piece_color(block.kind);
+        color.a *= alpha;
+        self.draw_cell(ctx, position, color)
     }
-    fn blocks_mut(&mut self) -> &mut Vec<Block> { &mut self.blocks }
-
-    fn do_rotate(&mut self) {
-        match self.rotation {
-            0 => {
-                self.blocks_mut()[0].col += 1;
-                self.blocks_mut()[0].y_pos_top += -30 as f32;
-
-                self.blocks_mut()[2].col += 1;
-                self.blocks_mut()[2].y_pos_top += 30 as f32;
-
-                self.blocks_mut()[3].col += 0;
-                self.blocks_mut()[3].y_pos_top += 60 as f32;
-            },
-            90 => {
-                self.blocks_mut()[0].col += -1;
-                self.blocks_mut()[0].y_pos_top += 30 as f32;
 
-                self.blocks_mut()[2].col += -1;
-                self.blocks_mut()[2].y_pos_top += -30 as f32;
+    // draws a piece in its spawn orientation with its bounding box at origin, for the side panels
+    fn draw_preview(&self, ctx: &mut Context, kind: PieceKind, origin: Vec2<f32>, alpha: f32) {
+        let cell_size = self.layout.cell_size as f32;
+        let mut color = piece_color(kind);
+        color.a *= alpha;
 
-                self.blocks_mut()[3].col += 0;
-                self.blocks_mut()[3].y_pos_top += -60 as f32;
-            },
-            _ => panic!("received unexpected rotation value: {}", self.rotation),
+        for block in kind.preview_blocks() {
+            let position = origin + Vec2::new(block.col as f32 * cell_size, block.row as f32 * cell_size);
+            self.draw_cell(ctx, position, color)
         }
-        self.enforce_boundaries_after_rotation();
-        self.rotation = (self.rotation + 90).rem_euclid(180);
     }
-}
 
-impl RightSkew {
-    fn new() -> Self {
-        Self {
-            blocks: vec![
-                Block {
-                    color: Color::rgba8(244, 127, 241, 166),
-                    col: 3,
-                    y_pos_top: -30 as f32,
-                },
-                Block {
-                    color: Color::rgba8(244, 127, 241, 166),
-                    col: 4,
-                    y_pos_top: -30 as f32,
-                },
-                Block {
-                    color: Color::rgba8(244, 127, 241, 166),
-                    col: 4,
-                    y_pos_top: -60 as f32,
-                },
-                Block {
-                    color: Color::rgba8(244, 127, 241, 166),
-                    col: 5,
-                    y_pos_top: -60 as f32,
-                },
-            ],
-            rotation: 0,
-        }
+    fn draw_cell(&self, ctx: &mut Context, position: Vec2<f32>, color: Color) {
+        let scale = self.layout.cell_size as f32 / 16.0;
+        self.block_texture.draw(
+            ctx,
+            DrawParams::new()
+                .position(position)
+                .color(color)
+                .scale(Vec2::new(scale, scale))
+        )
     }
 }
 
-#[derive(Clone)]
-struct LeftSkew {
-    blocks: Vec<Block>,
-    rotation: u32,
-}
-
-impl Piece for LeftSkew {
-    fn blocks(&self) -> &Vec<Block> {
-        &self.blocks
-    }
-    fn blocks_mut(&mut self) -> &mut Vec<Block> { &mut self.blocks }
-
-    fn do_rotate(&mut self) {
-        match self.rotation {
-            0 => {
-                self.blocks_mut()[1].col += -1;
-                self.blocks_mut()[1].y_pos_top += 30 as f32;
-
-                self.blocks_mut()[2].col += 2;
-                self.blocks_mut()[2].y_pos_top += 0 as f32;
-
-                self.blocks_mut()[3].col += 1;
-                self.blocks_mut()[3].y_pos_top += 30 as f32;
-            },
-            90 => {
-                self.blocks_mut()[1].col += 1;
-                self.blocks_mut()[1].y_pos_top += -30 as f32;
-
-                self.blocks_mut()[2].col += -2;
-                self.blocks_mut()[2].y_pos_top += 0 as f32;
-
-                self.blocks_mut()[3].col += -1;
-                self.blocks_mut()[3].y_pos_top += -30 as f32;
-            },
-            _ => panic!("received unexpected rotation value: {}", self.rotation),
-        }
-        self.enforce_boundaries_after_rotation();
-        self.rotation = (self.rotation + 90).rem_euclid(180);
+// the usual one letter names, for callouts
+fn piece_letter(kind: PieceKind) -> &'static str {
+    match kind {
+        PieceKind::Square => "O",
+        PieceKind::Straight => "I",
+        PieceKind::T => "T",
+        PieceKind::RightL => "L",
+        PieceKind::LeftL => "J",
+        PieceKind::RightSkew => "S",
+        PieceKind::LeftSkew => "Z",
     }
 }
 
-impl LeftSkew {
-    fn new() -> Self {
-        Self {
-            blocks: vec![
-                Block {
-                    color: Color::rgba8(245, 96, 127, 225),
-                    col: 4,
-                    y_pos_top: -30 as f32,
-                },
-                Block {
-                    color: Color::rgba8(245, 96, 127, 225),
-                    col: 5,
-                    y_pos_top: -30 as f32,
-                },
-                Block {
-                    color: Color::rgba8(245, 96, 127, 225),
-                    col: 3,
-                    y_pos_top: -60 as f32,
-                },
-                Block {
-                    color: Color::rgba8(245, 96, 127, 225),
-                    col: 4,
-                    y_pos_top: -60 as f32,
-                },
-            ],
-            rotation: 0,
-        }
+fn piece_color(kind: PieceKind) -> Color {
+    match kind {
+        PieceKind::Square => Color::rgba8(245, 40, 145, 204),
+        PieceKind::Straight => Color::rgba8(61, 139, 232, 117),
+        PieceKind::T => Color::rgba8(47, 94, 68, 196),
+        PieceKind::RightL => Color::rgba8(249, 134, 36, 224),
+        PieceKind::LeftL => Color::rgba8(176, 99, 246, 199),
+        PieceKind::RightSkew => Color::rgba8(244, 127, 241, 166),
+        PieceKind::LeftSkew => Color::rgba8(245, 96, 127, 225),
     }
 }
 
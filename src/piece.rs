@@ -0,0 +1,403 @@
+use crate::board::{Block, Board};
+use crate::rotation::{
+    KickTable, Rotation, RotationDirection, HALF_TURN_KICKS, JLSTZ_CLOCKWISE_KICKS,
+    JLSTZ_COUNTER_CLOCKWISE_KICKS, SQUARE_KICKS, STRAIGHT_CLOCKWISE_KICKS,
+    STRAIGHT_COUNTER_CLOCKWISE_KICKS,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PieceKind {
+    Square,
+    Straight,
+    T,
+    RightL,
+    LeftL,
+    RightSkew,
+    LeftSkew,
+}
+
+impl PieceKind {
+    pub const ALL: [PieceKind; 7] = [
+        PieceKind::Square,
+        PieceKind::Straight,
+        PieceKind::T,
+        PieceKind::RightL,
+        PieceKind::LeftL,
+        PieceKind::RightSkew,
+        PieceKind::LeftSkew,
+    ];
+
+    // the piece's cells in its spawn orientation, relative to the top left of its bounding box
+    pub fn preview_blocks(self) -> Vec<Block> {
+        let shapes = match self {
+            PieceKind::Square => &SQUARE_SHAPES,
+            PieceKind::Straight => &STRAIGHT_SHAPES,
+            PieceKind::T => &T_SHAPES,
+            PieceKind::RightL => &RIGHT_L_SHAPES,
+            PieceKind::LeftL => &LEFT_L_SHAPES,
+            PieceKind::RightSkew => &RIGHT_SKEW_SHAPES,
+            PieceKind::LeftSkew => &LEFT_SKEW_SHAPES,
+        };
+        shape_blocks(self, &shapes[Rotation::Spawn.index()], 0, 0)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    Left,
+    Right,
+}
+
+// position of the top left corner of the piece's bounding box, and its orientation
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PieceState {
+    pub x: i32,
+    pub y: i32,
+    pub rotation: Rotation,
+}
+
+impl PieceState {
+    // centers a bounding box of the given width, rounding to the left, with the spawn
+    // orientation's lower row on the board's spawn row
+    fn spawn(board: &Board, box_width: usize) -> Self {
+        Self {
+            x: (board.width() - box_width) as i32 / 2,
+            y: board.spawn_row() - 1,
+            rotation: Rotation::Spawn,
+        }
+    }
+}
+
+// one mask per row of the bounding box for each orientation, in the order of Rotation;
+// bit n is column n, so the literals below read mirrored left to right
+type Shapes = [[u16; 4]; 4];
+
+const SQUARE_SHAPES: Shapes = [
+    [0b0110, 0b0110, 0b0000, 0b0000],
+    [0b0110, 0b0110, 0b0000, 0b0000],
+    [0b0110, 0b0110, 0b0000, 0b0000],
+    [0b0110, 0b0110, 0b0000, 0b0000],
+];
+
+const STRAIGHT_SHAPES: Shapes = [
+    [0b0000, 0b1111, 0b0000, 0b0000],
+    [0b0100, 0b0100, 0b0100, 0b0100],
+    [0b0000, 0b0000, 0b1111, 0b0000],
+    [0b0010, 0b0010, 0b0010, 0b0010],
+];
+
+const T_SHAPES: Shapes = [
+    [0b010, 0b111, 0b000, 0b000],
+    [0b010, 0b110, 0b010, 0b000],
+    [0b000, 0b111, 0b010, 0b000],
+    [0b010, 0b011, 0b010, 0b000],
+];
+
+const RIGHT_L_SHAPES: Shapes = [
+    [0b100, 0b111, 0b000, 0b000],
+    [0b010, 0b010, 0b110, 0b000],
+    [0b000, 0b111, 0b001, 0b000],
+    [0b011, 0b010, 0b010, 0b000],
+];
+
+const LEFT_L_SHAPES: Shapes = [
+    [0b001, 0b111, 0b000, 0b000],
+    [0b110, 0b010, 0b010, 0b000],
+    [0b000, 0b111, 0b100, 0b000],
+    [0b010, 0b010, 0b011, 0b000],
+];
+
+const RIGHT_SKEW_SHAPES: Shapes = [
+    [0b110, 0b011, 0b000, 0b000],
+    [0b010, 0b110, 0b100, 0b000],
+    [0b000, 0b110, 0b011, 0b000],
+    [0b001, 0b011, 0b010, 0b000],
+];
+
+const LEFT_SKEW_SHAPES: Shapes = [
+    [0b011, 0b110, 0b000, 0b000],
+    [0b100, 0b110, 0b010, 0b000],
+    [0b000, 0b011, 0b110, 0b000],
+    [0b010, 0b011, 0b001, 0b000],
+];
+
+pub trait CloneBoxPiece {
+    fn clone_box(&self) -> Box<dyn Piece>;
+}
+
+impl<T> CloneBoxPiece for T
+    where
+        T: 'static + Piece + Clone,
+{
+    fn clone_box(&self) -> Box<dyn Piece> {
+        Box::new(self.clone())
+    }
+}
+
+// Send so that an Engine can be moved to another thread
+pub trait Piece : CloneBoxPiece + Send {
+    fn kind(&self) -> PieceKind;
+    fn shapes(&self) -> &'static Shapes;
+    fn state(&self) -> &PieceState;
+    fn state_mut(&mut self) -> &mut PieceState;
+
+    fn kicks(&self, direction: RotationDirection) -> &'static KickTable {
+        match direction {
+            RotationDirection::Clockwise => &JLSTZ_CLOCKWISE_KICKS,
+            RotationDirection::CounterClockwise => &JLSTZ_COUNTER_CLOCKWISE_KICKS,
+            RotationDirection::Half => &HALF_TURN_KICKS,
+        }
+    }
+
+    fn shape(&self, rotation: Rotation) -> &'static [u16; 4] {
+        &self.shapes()[rotation.index()]
+    }
+
+    fn blocks(&self) -> Vec<Block> {
+        let state = self.state();
+        shape_blocks(self.kind(), self.shape(state.rotation), state.x, state.y)
+    }
+
+    fn shift(&mut self, board: &Board, direction: Direction) -> bool {
+        let state = *self.state();
+        let x = match direction {
+            Direction::Left => state.x - 1,
+            Direction::Right => state.x + 1,
+        };
+
+        if !board.fits(self, x, state.y, state.rotation) {
+            return false
+        }
+
+        self.state_mut().x = x;
+        true
+    }
+
+    fn drop_row(&mut self, board: &Board) -> bool {
+        let state = *self.state();
+
+        if !board.fits(self, state.x, state.y + 1, state.rotation) {
+            return false
+        }
+
+        self.state_mut().y += 1;
+        true
+    }
+
+    // tries each SRS kick in turn and takes the first that fits, returning its index
+    fn rotate(&mut self, board: &Board, direction: RotationDirection) -> Option<usize> {
+        let state = *self.state();
+        let rotation = state.rotation.rotated(direction);
+
+        for (kick, &(x_kick, y_kick)) in self.kicks(direction)[state.rotation.index()].iter().enumerate() {
+            let x = state.x + x_kick;
+            let y = state.y - y_kick;
+            if board.fits(self, x, y, rotation) {
+                *self.state_mut() = PieceState { x, y, rotation };
+                return Some(kick)
+            }
+        }
+
+        None
+    }
+}
+
+#[derive(Clone)]
+pub struct Square {
+    state: PieceState,
+}
+
+impl Piece for Square {
+    fn kind(&self) -> PieceKind { PieceKind::Square }
+    fn shapes(&self) -> &'static Shapes { &SQUARE_SHAPES }
+    fn state(&self) -> &PieceState { &self.state }
+    fn state_mut(&mut self) -> &mut PieceState { &mut self.state }
+    fn kicks(&self, _: RotationDirection) -> &'static KickTable { &SQUARE_KICKS }
+}
+
+impl Square {
+    pub fn new(board: &Board) -> Self {
+        Self { state: PieceState::spawn(board, 4) }
+    }
+}
+
+#[derive(Clone)]
+pub struct Straight {
+    state: PieceState,
+}
+
+impl Piece for Straight {
+    fn kind(&self) -> PieceKind { PieceKind::Straight }
+    fn shapes(&self) -> &'static Shapes { &STRAIGHT_SHAPES }
+    fn state(&self) -> &PieceState { &self.state }
+    fn state_mut(&mut self) -> &mut PieceState { &mut self.state }
+    fn kicks(&self, direction: RotationDirection) -> &'static KickTable {
+        match direction {
+            RotationDirection::Clockwise => &STRAIGHT_CLOCKWISE_KICKS,
+            RotationDirection::CounterClockwise => &STRAIGHT_COUNTER_CLOCKWISE_KICKS,
+            RotationDirection::Half => &HALF_TURN_KICKS,
+        }
+    }
+}
+
+impl Straight {
+    pub fn new(board: &Board) -> Self {
+        Self { state: PieceState::spawn(board, 4) }
+    }
+}
+
+#[derive(Clone)]
+pub struct T {
+    state: PieceState,
+}
+
+impl Piece for T {
+    fn kind(&self) -> PieceKind { PieceKind::T }
+    fn shapes(&self) -> &'static Shapes { &T_SHAPES }
+    fn state(&self) -> &PieceState { &self.state }
+    fn state_mut(&mut self) -> &mut PieceState { &mut self.state }
+}
+
+impl T {
+    pub fn new(board: &Board) -> Self {
+        Self { state: PieceState::spawn(board, 3) }
+    }
+}
+
+#[derive(Clone)]
+pub struct RightL {
+    state: PieceState,
+}
+
+impl Piece for RightL {
+    fn kind(&self) -> PieceKind { PieceKind::RightL }
+    fn shapes(&self) -> &'static Shapes { &RIGHT_L_SHAPES }
+    fn state(&self) -> &PieceState { &self.state }
+    fn state_mut(&mut self) -> &mut PieceState { &mut self.state }
+}
+
+impl RightL {
+    pub fn new(board: &Board) -> Self {
+        Self { state: PieceState::spawn(board, 3) }
+    }
+}
+
+#[derive(Clone)]
+pub struct LeftL {
+    state: PieceState,
+}
+
+impl Piece for LeftL {
+    fn kind(&self) -> PieceKind { PieceKind::LeftL }
+    fn shapes(&self) -> &'static Shapes { &LEFT_L_SHAPES }
+    fn state(&self) -> &PieceState { &self.state }
+    fn state_mut(&mut self) -> &mut PieceState { &mut self.state }
+}
+
+impl LeftL {
+    pub fn new(board: &Board) -> Self {
+        Self { state: PieceState::spawn(board, 3) }
+    }
+}
+
+#[derive(Clone)]
+pub struct RightSkew {
+    state: PieceState,
+}
+
+impl Piece for RightSkew {
+    fn kind(&self) -> PieceKind { PieceKind::RightSkew }
+    fn shapes(&self) -> &'static Shapes { &RIGHT_SKEW_SHAPES }
+    fn state(&self) -> &PieceState { &self.state }
+    fn state_mut(&mut self) -> &mut PieceState { &mut self.state }
+}
+
+impl RightSkew {
+    pub fn new(board: &Board) -> Self {
+        Self { state: PieceState::spawn(board, 3) }
+    }
+}
+
+#[derive(Clone)]
+pub struct LeftSkew {
+    state: PieceState,
+}
+
+impl Piece for LeftSkew {
+    fn kind(&self) -> PieceKind { PieceKind::LeftSkew }
+    fn shapes(&self) -> &'static Shapes { &LEFT_SKEW_SHAPES }
+    fn state(&self) -> &PieceState { &self.state }
+    fn state_mut(&mut self) -> &mut PieceState { &mut self.state }
+}
+
+impl LeftSkew {
+    pub fn new(board: &Board) -> Self {
+        Self { state: PieceState::spawn(board, 3) }
+    }
+}
+
+fn shape_blocks(kind: PieceKind, shape: &[u16; 4], x: i32, y: i32) -> Vec<Block> {
+    let mut blocks = Vec::with_capacity(4);
+    for (row, bits) in shape.iter().enumerate() {
+        for col in 0..16 {
+            if bits & (1 << col) != 0 {
+                blocks.push(Block {
+                    kind,
+                    row: y + row as i32,
+                    col: x + col,
+                })
+            }
+        }
+    }
+    blocks
+}
+
+pub fn new_piece(kind: PieceKind, board: &Board) -> Box<dyn Piece> {
+    match kind {
+        PieceKind::Square => Box::new(Square::new(board)),
+        PieceKind::Straight => Box::new(Straight::new(board)),
+        PieceKind::T => Box::new(T::new(board)),
+        PieceKind::RightL => Box::new(RightL::new(board)),
+        PieceKind::LeftL => Box::new(LeftL::new(board)),
+        PieceKind::RightSkew => Box::new(RightSkew::new(board)),
+        PieceKind::LeftSkew => Box::new(LeftSkew::new(board)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::BoardConfig;
+
+    #[test]
+    fn rotate_kicks_off_the_wall() {
+        let board = Board::new(&BoardConfig::default());
+        let mut piece = T::new(&board);
+        piece.state = PieceState { x: -1, y: 30, rotation: Rotation::Right };
+
+        // R -> 2 fails in place against the left wall and takes the second kick, one column right
+        assert_eq!(piece.rotate(&board, RotationDirection::Clockwise), Some(1));
+        assert_eq!(piece.state, PieceState { x: 0, y: 30, rotation: Rotation::Reverse });
+    }
+
+    #[test]
+    fn straight_uses_its_own_kicks() {
+        let board = Board::new(&BoardConfig::default());
+        let mut piece = Straight::new(&board);
+        piece.state = PieceState { x: -1, y: 30, rotation: Rotation::Left };
+
+        // L -> 0 pokes out of the left wall in place, so the I kicks one column right
+        assert_eq!(piece.rotate(&board, RotationDirection::Clockwise), Some(1));
+        assert_eq!(piece.state, PieceState { x: 0, y: 30, rotation: Rotation::Spawn });
+    }
+
+    #[test]
+    fn square_never_kicks() {
+        let board = Board::new(&BoardConfig::default());
+        let mut piece = Square::new(&board);
+        let state = piece.state;
+
+        assert_eq!(piece.rotate(&board, RotationDirection::Half), Some(0));
+        assert_eq!((piece.state.x, piece.state.y), (state.x, state.y));
+    }
+}
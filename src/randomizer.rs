@@ -0,0 +1,174 @@
+use std::collections::VecDeque;
+
+use rand::seq::SliceRandom;
+use rand::{Rng, RngCore};
+
+use crate::piece::PieceKind;
+
+pub trait Randomizer: Send {
+    fn next(&mut self, rng: &mut dyn RngCore) -> PieceKind;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RandomizerKind {
+    SevenBag,
+    FourteenBag,
+    PureRandom,
+    // TGM1 style, rolling up to 4 times for a piece outside the last 4
+    History,
+    // NES style, rerolling once on a repeat
+    Nes,
+}
+
+impl RandomizerKind {
+    pub fn build(self) -> Box<dyn Randomizer> {
+        match self {
+            RandomizerKind::SevenBag => Box::new(BagRandomizer::new(1)),
+            RandomizerKind::FourteenBag => Box::new(BagRandomizer::new(2)),
+            RandomizerKind::PureRandom => Box::new(PureRandomizer),
+            RandomizerKind::History => Box::new(HistoryRandomizer::new(4)),
+            RandomizerKind::Nes => Box::new(NesRandomizer::default()),
+        }
+    }
+}
+
+// deals shuffled bags holding each piece `copies` times
+pub struct BagRandomizer {
+    copies: usize,
+    bag: Vec<PieceKind>,
+}
+
+impl BagRandomizer {
+    pub fn new(copies: usize) -> Self {
+        Self {
+            copies,
+            bag: Vec::with_capacity(copies * PieceKind::ALL.len()),
+        }
+    }
+}
+
+impl Randomizer for BagRandomizer {
+    fn next(&mut self, rng: &mut dyn RngCore) -> PieceKind {
+        if self.bag.is_empty() {
+            for _ in 0..self.copies {
+                self.bag.extend_from_slice(&PieceKind::ALL);
+            }
+            self.bag.shuffle(rng);
+        }
+        self.bag.pop().unwrap()
+    }
+}
+
+pub struct PureRandomizer;
+
+impl Randomizer for PureRandomizer {
+    fn next(&mut self, rng: &mut dyn RngCore) -> PieceKind {
+        *PieceKind::ALL.choose(rng).unwrap()
+    }
+}
+
+pub struct HistoryRandomizer {
+    // rolls for a piece outside the history, the last one kept either way
+    rolls: usize,
+    // most recent last
+    history: VecDeque<PieceKind>,
+    first: bool,
+}
+
+impl HistoryRandomizer {
+    pub fn new(rolls: usize) -> Self {
+        Self {
+            rolls,
+            // TGM1 starts from a history of four Zs
+            history: VecDeque::from([PieceKind::LeftSkew; 4]),
+            first: true,
+        }
+    }
+}
+
+impl Randomizer for HistoryRandomizer {
+    fn next(&mut self, rng: &mut dyn RngCore) -> PieceKind {
+        let kind = if self.first {
+            // the first piece is never one that forces an overhang
+            self.first = false;
+            *[PieceKind::Straight, PieceKind::T, PieceKind::RightL, PieceKind::LeftL]
+                .choose(rng)
+                .unwrap()
+        } else {
+            let mut kind = *PieceKind::ALL.choose(rng).unwrap();
+            for _ in 1..self.rolls {
+                if !self.history.contains(&kind) {
+                    break
+                }
+                kind = *PieceKind::ALL.choose(rng).unwrap();
+            }
+            kind
+        };
+
+        self.history.pop_front();
+        self.history.push_back(kind);
+        kind
+    }
+}
+
+#[derive(Default)]
+pub struct NesRandomizer {
+    last: Option<PieceKind>,
+}
+
+impl Randomizer for NesRandomizer {
+    fn next(&mut self, rng: &mut dyn RngCore) -> PieceKind {
+        // the eighth roll stands for "reroll", as does repeating the last piece
+        let roll = rng.gen_range(0..=PieceKind::ALL.len());
+        let kind = match PieceKind::ALL.get(roll) {
+            Some(&kind) if Some(kind) != self.last => kind,
+            _ => *PieceKind::ALL.choose(rng).unwrap(),
+        };
+
+        self.last = Some(kind);
+        kind
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+
+    fn deal(randomizer: &mut dyn Randomizer, seed: u64, count: usize) -> Vec<PieceKind> {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        (0..count).map(|_| randomizer.next(&mut rng)).collect()
+    }
+
+    fn holds_each_piece(pieces: &[PieceKind], copies: usize) -> bool {
+        PieceKind::ALL.iter().all(|kind| pieces.iter().filter(|&piece| piece == kind).count() == copies)
+    }
+
+    #[test]
+    fn bags_hold_each_piece_once_per_copy() {
+        let pieces = deal(&mut BagRandomizer::new(1), 1, 7 * 20);
+        assert!(pieces.chunks(7).all(|bag| holds_each_piece(bag, 1)));
+
+        let pieces = deal(&mut BagRandomizer::new(2), 1, 14 * 20);
+        assert!(pieces.chunks(14).all(|bag| holds_each_piece(bag, 2)));
+    }
+
+    #[test]
+    fn nes_rerolls_a_repeat() {
+        // a repeat needs the first roll to repeat or ask for a reroll and the reroll to repeat,
+        // 1 in 28 against 1 in 7 for pure random
+        let pieces = deal(&mut NesRandomizer::default(), 1, 7000);
+        let repeats = pieces.windows(2).filter(|pair| pair[0] == pair[1]).count();
+        assert!(repeats < 500, "{} repeats", repeats);
+    }
+
+    #[test]
+    fn history_never_deals_s_z_or_o_first() {
+        for seed in 0..100 {
+            let first = deal(&mut HistoryRandomizer::new(4), seed, 1)[0];
+            assert!(!matches!(first, PieceKind::RightSkew | PieceKind::LeftSkew | PieceKind::Square));
+        }
+    }
+}
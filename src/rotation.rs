@@ -0,0 +1,126 @@
+// the four SRS orientations, named 0, R, 2 and L in most references
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rotation {
+    Spawn,
+    Right,
+    Reverse,
+    Left,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RotationDirection {
+    Clockwise,
+    CounterClockwise,
+    Half,
+}
+
+impl Rotation {
+    pub fn rotated(self, direction: RotationDirection) -> Self {
+        let quarter_turns = match direction {
+            RotationDirection::Clockwise => 1,
+            RotationDirection::CounterClockwise => 3,
+            RotationDirection::Half => 2,
+        };
+        match (self.index() + quarter_turns) % 4 {
+            0 => Rotation::Spawn,
+            1 => Rotation::Right,
+            2 => Rotation::Reverse,
+            _ => Rotation::Left,
+        }
+    }
+
+    pub fn index(self) -> usize {
+        self as usize
+    }
+}
+
+// offsets tried in order when rotating, indexed by the orientation being rotated from.
+// they are (x, y) with y pointing up, as the tables are usually published
+pub(crate) type KickTable = [&'static [(i32, i32)]; 4];
+
+pub(crate) const JLSTZ_CLOCKWISE_KICKS: KickTable = [
+    // 0 -> R
+    &[(0, 0), (-1, 0), (-1, 1), (0, -2), (-1, -2)],
+    // R -> 2
+    &[(0, 0), (1, 0), (1, -1), (0, 2), (1, 2)],
+    // 2 -> L
+    &[(0, 0), (1, 0), (1, 1), (0, -2), (1, -2)],
+    // L -> 0
+    &[(0, 0), (-1, 0), (-1, -1), (0, 2), (-1, 2)],
+];
+
+pub(crate) const JLSTZ_COUNTER_CLOCKWISE_KICKS: KickTable = [
+    // 0 -> L
+    &[(0, 0), (1, 0), (1, 1), (0, -2), (1, -2)],
+    // R -> 0
+    &[(0, 0), (1, 0), (1, -1), (0, 2), (1, 2)],
+    // 2 -> R
+    &[(0, 0), (-1, 0), (-1, 1), (0, -2), (-1, -2)],
+    // L -> 2
+    &[(0, 0), (-1, 0), (-1, -1), (0, 2), (-1, 2)],
+];
+
+pub(crate) const STRAIGHT_CLOCKWISE_KICKS: KickTable = [
+    // 0 -> R
+    &[(0, 0), (-2, 0), (1, 0), (-2, -1), (1, 2)],
+    // R -> 2
+    &[(0, 0), (-1, 0), (2, 0), (-1, 2), (2, -1)],
+    // 2 -> L
+    &[(0, 0), (2, 0), (-1, 0), (2, 1), (-1, -2)],
+    // L -> 0
+    &[(0, 0), (1, 0), (-2, 0), (1, -2), (-2, 1)],
+];
+
+pub(crate) const STRAIGHT_COUNTER_CLOCKWISE_KICKS: KickTable = [
+    // 0 -> L
+    &[(0, 0), (-1, 0), (2, 0), (-1, 2), (2, -1)],
+    // R -> 0
+    &[(0, 0), (2, 0), (-1, 0), (2, 1), (-1, -2)],
+    // 2 -> R
+    &[(0, 0), (1, 0), (-2, 0), (1, -2), (-2, 1)],
+    // L -> 2
+    &[(0, 0), (-2, 0), (1, 0), (-2, -1), (1, 2)],
+];
+
+// the SRS+ 180 table, shared by every piece but the square
+pub(crate) const HALF_TURN_KICKS: KickTable = [
+    // 0 -> 2
+    &[(0, 0), (0, 1), (1, 1), (-1, 1), (1, 0), (-1, 0)],
+    // R -> L
+    &[(0, 0), (1, 0), (1, 2), (1, 1), (0, 2), (0, 1)],
+    // 2 -> 0
+    &[(0, 0), (0, -1), (-1, -1), (1, -1), (-1, 0), (1, 0)],
+    // L -> R
+    &[(0, 0), (-1, 0), (-1, 2), (-1, 1), (0, 2), (0, 1)],
+];
+
+// the square looks the same in every orientation, so it never needs to move
+pub(crate) const SQUARE_KICKS: KickTable = [&[(0, 0)]; 4];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rotated() {
+        assert_eq!(Rotation::Spawn.rotated(RotationDirection::Clockwise), Rotation::Right);
+        assert_eq!(Rotation::Spawn.rotated(RotationDirection::CounterClockwise), Rotation::Left);
+        assert_eq!(Rotation::Right.rotated(RotationDirection::Half), Rotation::Left);
+        assert_eq!(Rotation::Left.rotated(RotationDirection::Clockwise), Rotation::Spawn);
+    }
+
+    #[test]
+    fn every_kick_list_starts_in_place() {
+        let tables = [
+            &JLSTZ_CLOCKWISE_KICKS,
+            &JLSTZ_COUNTER_CLOCKWISE_KICKS,
+            &STRAIGHT_CLOCKWISE_KICKS,
+            &STRAIGHT_COUNTER_CLOCKWISE_KICKS,
+            &HALF_TURN_KICKS,
+            &SQUARE_KICKS,
+        ];
+        for table in tables {
+            assert!(table.iter().all(|kicks| kicks[0] == (0, 0)));
+        }
+    }
+}
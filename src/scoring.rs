@@ -0,0 +1,179 @@
+use crate::chains::Chains;
+use crate::spin::SpinKind;
+
+// the point values a game is scored with
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScoringTable {
+    // line clears, spins, combos and perfect clears times the level, with back-to-back difficult
+    // clears worth half again
+    Guideline,
+    // line clears times one more than the level, and nothing for spins, combos or, unless
+    // configured, perfect clears
+    Nes,
+}
+
+// what a piece did as it locked
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Clear {
+    pub lines: u32,
+    pub spin: Option<SpinKind>,
+    // the board was left empty
+    pub perfect: bool,
+}
+
+impl Clear {
+    // clears that keep a back-to-back chain going, breaking it unless spins with no lines
+    pub fn is_difficult(&self) -> bool {
+        self.lines >= 4 || (self.lines > 0 && self.spin.is_some())
+    }
+}
+
+pub struct Scoring {
+    table: ScoringTable,
+    perfect_clear_bonus: Option<[u32; 4]>,
+    score: u64,
+}
+
+impl Scoring {
+    pub(crate) fn new(table: ScoringTable, perfect_clear_bonus: Option<[u32; 4]>) -> Self {
+        Self {
+            table,
+            perfect_clear_bonus,
+            score: 0,
+        }
+    }
+
+    pub fn table(&self) -> ScoringTable {
+        self.table
+    }
+
+    pub fn score(&self) -> u64 {
+        self.score
+    }
+
+    pub(crate) fn award_soft_drop(&mut self, cells: u32) {
+        self.score += cells as u64;
+    }
+
+    pub(crate) fn award_hard_drop(&mut self, cells: u32) {
+        let points_per_cell = match self.table {
+            ScoringTable::Guideline => 2,
+            // the NES has no hard drop, so it counts the same as dropping by hand
+            ScoringTable::Nes => 1,
+        };
+        self.score += cells as u64 * points_per_cell;
+    }
+
+    // scores a piece locking at the given level, with the chains already updated for it
+    pub(crate) fn award_lock(&mut self, clear: Clear, chains: Chains, level: u32) {
+        let back_to_back = clear.is_difficult() && chains.is_back_to_back();
+        let combo = chains.combo.unwrap_or(0);
+        let perfect = if clear.perfect { self.perfect_clear_bonus(clear.lines, back_to_back) } else { 0 };
+
+        let points = match self.table {
+            ScoringTable::Guideline => {
+                let lines = match (clear.spin, clear.lines) {
+                    (None, 0) => 0,
+                    (None, 1) => 100,
+                    (None, 2) => 300,
+                    (None, 3) => 500,
+                    (None, _) => 800,
+                    (Some(SpinKind::MiniTSpin | SpinKind::AllSpin(_)), 0) => 100,
+                    (Some(SpinKind::MiniTSpin | SpinKind::AllSpin(_)), 1) => 200,
+                    (Some(SpinKind::MiniTSpin | SpinKind::AllSpin(_)), _) => 400,
+                    (Some(SpinKind::TSpin), 0) => 400,
+                    (Some(SpinKind::TSpin), 1) => 800,
+                    (Some(SpinKind::TSpin), 2) => 1200,
+                    (Some(SpinKind::TSpin), _) => 1600,
+                };
+                let lines = if back_to_back { lines * 3 / 2 } else { lines };
+                (lines + 50 * combo as u64 + perfect) * level.max(1) as u64
+            },
+            ScoringTable::Nes => {
+                let lines = match clear.lines {
+                    0 => 0,
+                    1 => 40,
+                    2 => 100,
+                    3 => 300,
+                    _ => 1200,
+                };
+                (lines + perfect) * (level as u64 + 1)
+            },
+        };
+        self.score += points;
+    }
+
+    // points for a perfect clear before the level multiplier, from the configured bonus if there is one
+    fn perfect_clear_bonus(&self, lines: u32, back_to_back: bool) -> u64 {
+        let index = lines.clamp(1, 4) as usize - 1;
+        if let Some(bonus) = self.perfect_clear_bonus {
+            return bonus[index] as u64
+        }
+
+        match self.table {
+            ScoringTable::Guideline if back_to_back && lines >= 4 => 3200,
+            ScoringTable::Guideline => [800, 1200, 1800, 2000][index],
+            ScoringTable::Nes => 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // updates the chains for each clear and scores it at level 1, returning the running score
+    fn play(table: ScoringTable, clears: &[Clear]) -> u64 {
+        let mut scoring = Scoring::new(table, None);
+        let mut chains = Chains::default();
+        for &clear in clears {
+            chains.update(clear);
+            scoring.award_lock(clear, chains, 1);
+        }
+        scoring.score()
+    }
+
+    fn lines(lines: u32) -> Clear {
+        Clear { lines, spin: None, perfect: false }
+    }
+
+    #[test]
+    fn back_to_back_tetris() {
+        // the second tetris is worth half again, plus a combo of 1
+        assert_eq!(play(ScoringTable::Guideline, &[lines(4), lines(4)]), 800 + 1200 + 50);
+        // a single in between breaks the chain
+        assert_eq!(play(ScoringTable::Guideline, &[lines(4), lines(0), lines(1), lines(4)]), 800 + 100 + 800 + 50);
+    }
+
+    #[test]
+    fn t_spins_keep_back_to_back() {
+        let tsd = Clear { lines: 2, spin: Some(SpinKind::TSpin), perfect: false };
+        let t_spin = Clear { lines: 0, spin: Some(SpinKind::TSpin), perfect: false };
+
+        assert_eq!(play(ScoringTable::Guideline, &[tsd]), 1200);
+        assert_eq!(play(ScoringTable::Guideline, &[tsd, t_spin, lines(4)]), 1200 + 400 + 1200);
+    }
+
+    #[test]
+    fn perfect_clear() {
+        let perfect_single = Clear { lines: 1, spin: None, perfect: true };
+        let perfect_tetris = Clear { lines: 4, spin: None, perfect: true };
+
+        assert_eq!(play(ScoringTable::Guideline, &[perfect_single]), 100 + 800);
+        assert_eq!(play(ScoringTable::Guideline, &[lines(4), perfect_tetris]), 800 + 1200 + 50 + 3200);
+
+        let mut scoring = Scoring::new(ScoringTable::Guideline, Some([1, 2, 3, 4]));
+        scoring.award_lock(perfect_single, Chains { combo: Some(0), back_to_back: None }, 2);
+        assert_eq!(scoring.score(), (100 + 1) * 2);
+    }
+
+    #[test]
+    fn nes_table() {
+        let mut scoring = Scoring::new(ScoringTable::Nes, None);
+        let chains = Chains { combo: Some(0), back_to_back: Some(0) };
+        scoring.award_lock(lines(4), chains, 0);
+        scoring.award_lock(lines(2), chains, 9);
+        scoring.award_hard_drop(10);
+        assert_eq!(scoring.score(), 1200 + 1000 + 10);
+    }
+}
@@ -0,0 +1,149 @@
+use crate::board::Board;
+use crate::piece::{Piece, PieceKind};
+use crate::rotation::{Rotation, RotationDirection};
+
+// the last kick of a quarter turn, which turns a mini T-spin into a full one
+const TST_KICK: usize = 4;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SpinKind {
+    TSpin,
+    MiniTSpin,
+    // any other piece rotated into a spot it cannot move out of, scored like a mini T-spin
+    AllSpin(PieceKind),
+}
+
+// the rotation that last moved the active piece, with the index of the kick it took
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct LastRotation {
+    pub(crate) direction: RotationDirection,
+    pub(crate) kick: usize,
+}
+
+// classifies a piece about to lock whose last move was a rotation, with all_spin also
+// checking pieces other than T and O
+pub(crate) fn detect_spin(
+    board: &Board,
+    piece: &dyn Piece,
+    last_rotation: Option<LastRotation>,
+    all_spin: bool,
+) -> Option<SpinKind> {
+    let last_rotation = last_rotation?;
+    match piece.kind() {
+        PieceKind::T => detect_t_spin(board, piece, last_rotation),
+        PieceKind::Square => None,
+        kind if all_spin && is_immobile(board, piece) => Some(SpinKind::AllSpin(kind)),
+        _ => None,
+    }
+}
+
+// the 3-corner rule: a T spins when 3 of the 4 corners around its center are filled, fully
+// when both corners it points towards are among them or it got there with the TST kick
+fn detect_t_spin(board: &Board, piece: &dyn Piece, last_rotation: LastRotation) -> Option<SpinKind> {
+    let state = piece.state();
+    let (left, right, top, bottom) = (state.x, state.x + 2, state.y, state.y + 2);
+    let [top_left, top_right, bottom_left, bottom_right] = [(top, left), (top, right), (bottom, left), (bottom, right)]
+        .map(|(row, col)| board.is_occupied(row, col));
+
+    let corners = [top_left, top_right, bottom_left, bottom_right].iter().filter(|&&filled| filled).count();
+    if corners < 3 {
+        return None
+    }
+
+    let front_corners = match state.rotation {
+        Rotation::Spawn => top_left && top_right,
+        Rotation::Right => top_right && bottom_right,
+        Rotation::Reverse => bottom_left && bottom_right,
+        Rotation::Left => top_left && bottom_left,
+    };
+    let tst_kick = last_rotation.direction != RotationDirection::Half && last_rotation.kick == TST_KICK;
+
+    if front_corners || tst_kick {
+        Some(SpinKind::TSpin)
+    } else {
+        Some(SpinKind::MiniTSpin)
+    }
+}
+
+// the piece can move neither left, right nor up
+fn is_immobile(board: &Board, piece: &dyn Piece) -> bool {
+    let state = piece.state();
+    [(-1, 0), (1, 0), (0, -1)]
+        .iter()
+        .all(|&(x, y)| !board.fits(piece, state.x + x, state.y + y, state.rotation))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::BoardConfig;
+    use crate::piece::{new_piece, PieceState};
+
+    const QUARTER_TURN: Option<LastRotation> = Some(LastRotation { direction: RotationDirection::Clockwise, kick: 0 });
+
+    fn t_at(board: &Board, x: i32, y: i32, rotation: Rotation) -> Box<dyn Piece> {
+        let mut piece = new_piece(PieceKind::T, board);
+        *piece.state_mut() = PieceState { x, y, rotation };
+        piece
+    }
+
+    // a T-spin double slot in the bottom two rows with an overhang on its left
+    fn tsd_board() -> Board {
+        let mut board = Board::new(&BoardConfig::default());
+        board.fill(39, 0b11_1110_1111);
+        board.fill(38, 0b11_1100_0111);
+        board.fill(37, 0b00_0000_1000);
+        board
+    }
+
+    #[test]
+    fn t_spin_double() {
+        let board = tsd_board();
+        let piece = t_at(&board, 3, 37, Rotation::Reverse);
+
+        assert_eq!(detect_spin(&board, piece.as_ref(), QUARTER_TURN, false), Some(SpinKind::TSpin));
+        assert_eq!(detect_spin(&board, piece.as_ref(), None, false), None);
+    }
+
+    #[test]
+    fn two_corners_is_no_spin() {
+        let mut board = Board::new(&BoardConfig::default());
+        board.fill(39, 0b11_1110_1111);
+        board.fill(38, 0b11_1100_0111);
+        let piece = t_at(&board, 3, 37, Rotation::Reverse);
+
+        assert_eq!(detect_spin(&board, piece.as_ref(), QUARTER_TURN, false), None);
+    }
+
+    #[test]
+    fn mini_t_spin_and_tst_kick_upgrade() {
+        // pointing up against the floor with only one of its front corners filled
+        let mut board = Board::new(&BoardConfig::default());
+        board.fill(38, 0b1);
+        let piece = t_at(&board, 0, 38, Rotation::Spawn);
+
+        assert_eq!(detect_spin(&board, piece.as_ref(), QUARTER_TURN, false), Some(SpinKind::MiniTSpin));
+
+        let tst_kick = Some(LastRotation { direction: RotationDirection::Clockwise, kick: TST_KICK });
+        assert_eq!(detect_spin(&board, piece.as_ref(), tst_kick, false), Some(SpinKind::TSpin));
+    }
+
+    #[test]
+    fn all_spin_needs_an_immobile_piece() {
+        let mut board = Board::new(&BoardConfig::default());
+        board.fill(39, 0b11_1111_1100);
+        board.fill(38, 0b11_1111_1000);
+        let mut piece = new_piece(PieceKind::RightSkew, &board);
+        *piece.state_mut() = PieceState { x: 0, y: 38, rotation: Rotation::Spawn };
+
+        // free to move up
+        assert_eq!(detect_spin(&board, piece.as_ref(), QUARTER_TURN, true), None);
+
+        board.fill(37, 0b110);
+        assert_eq!(
+            detect_spin(&board, piece.as_ref(), QUARTER_TURN, true),
+            Some(SpinKind::AllSpin(PieceKind::RightSkew))
+        );
+        assert_eq!(detect_spin(&board, piece.as_ref(), QUARTER_TURN, false), None);
+    }
+}